@@ -0,0 +1,64 @@
+//! Build script providing the ECharts bundle embedded with the `embed-echarts` feature.
+//!
+//! The bundle is vendored at `assets/echarts.min.js`, so builds need no network access.
+//! It must match `ECHARTS_SHA256` and is copied to `$OUT_DIR/echarts.min.js`, where
+//! `plotter.rs` includes it. Without the feature, nothing is done.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "embed-echarts")]
+    echarts::provide_bundle();
+}
+
+#[cfg(feature = "embed-echarts")]
+mod echarts {
+    use sha2::{Digest, Sha256};
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    /// The ECharts release embedded for `--offline`.
+    const ECHARTS_VERSION: &str = "5.6.0";
+
+    /// SHA-256 of `dist/echarts.min.js` of `ECHARTS_VERSION`, in lowercase hex.
+    ///
+    /// While empty, the build stops and prints the checksum of the vendored bundle, to be
+    /// verified against the official release and pinned here.
+    const ECHARTS_SHA256: &str = "";
+
+    /// Reads the vendored bundle, checks its checksum, and writes it to `OUT_DIR`.
+    pub fn provide_bundle() {
+        let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+        let local = manifest_dir.join("assets").join("echarts.min.js");
+        println!("cargo:rerun-if-changed={}", local.display());
+
+        let bundle = fs::read(&local).unwrap_or_else(|e| {
+            panic!(
+                "cannot read {} ({}); the embed-echarts feature needs dist/echarts.min.js of \
+                 ECharts {} there, e.g. from https://cdn.jsdelivr.net/npm/echarts@{}/dist/echarts.min.js",
+                local.display(),
+                e,
+                ECHARTS_VERSION,
+                ECHARTS_VERSION
+            )
+        });
+
+        let checksum = format!("{:x}", Sha256::digest(&bundle));
+        if ECHARTS_SHA256.is_empty() {
+            panic!(
+                "ECHARTS_SHA256 in build.rs is not pinned; the vendored ECharts {} bundle has \
+                 SHA-256 {}. Verify it against the official release and pin it.",
+                ECHARTS_VERSION, checksum
+            );
+        }
+        if checksum != ECHARTS_SHA256 {
+            panic!(
+                "the ECharts bundle has SHA-256 {}, but ECharts {} is expected to have {}; \
+                 replace assets/echarts.min.js",
+                checksum, ECHARTS_VERSION, ECHARTS_SHA256
+            );
+        }
+
+        let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+        fs::write(out_dir.join("echarts.min.js"), bundle).expect("cannot write to OUT_DIR");
+    }
+}
@@ -5,9 +5,21 @@
 //! The documentation comments on each field are used by `clap` to generate
 //! the help messages (`--help`).
 
-use clap::Parser;
+use crate::data_loader::parse_dtype;
+use clap::{ArgAction, Parser};
+use polars::prelude::DataType;
+use regex::Regex;
 use std::path::PathBuf;
 
+/// A worksheet of an Excel file, selected with `--sheet`.
+#[derive(Debug, Clone)]
+pub enum SheetSelector {
+    /// The sheet with this name.
+    Name(String),
+    /// The sheet at this zero-based position in the workbook.
+    Index(usize),
+}
+
 /// A tool to generate interactive scatter plots from various data formats.
 #[derive(Parser, Debug)]
 #[command(
@@ -16,15 +28,27 @@ use std::path::PathBuf;
     about = "A tool to generate interactive scatter plots from various data formats."
 )]
 pub struct Cli {
-    /// The input file or folder to scan for data.
-    #[arg(required = true)]
+    /// The input file or folder to scan for data, or `-` to read from stdin (see --format).
+    #[arg(
+        required_unless_present = "generate_completions",
+        default_value = ".",
+        hide_default_value = true
+    )]
     pub input_path: PathBuf,
 
+    /// Optional output path. A path ending in `.html` is used as the output file;
+    /// any other path is treated as an output directory, like `--output-dir`.
+    pub output_path: Option<PathBuf>,
+
     /// Directory to save the generated HTML plots.
     /// Defaults to saving next to each input file.
     #[arg(short = 'o', long = "output-dir")]
     pub output: Option<PathBuf>,
 
+    /// Print a completion script for the given shell to stdout and exit.
+    #[arg(long, value_name = "SHELL", value_parser = ["bash", "zsh", "fish", "powershell"], hide = true)]
+    pub generate_completions: Option<String>,
+
     /// Name of the column to use as the index (X-axis).
     /// This has the highest priority for index selection.
     #[arg(short = 'i', long)]
@@ -35,26 +59,424 @@ pub struct Cli {
     #[arg(short = 'f', long, default_value_t = false)]
     pub use_first_column: bool,
 
-    /// Comma-separated list of columns to plot (Y-axis).
+    /// Comma-separated list of columns to combine into a composite X-axis (e.g., `date,hour`).
+    /// Their values are joined into a single categorical column, which takes precedence
+    /// over --index; the source columns are not plotted.
+    #[arg(long, use_value_delimiter = true, value_delimiter = ',')]
+    pub multi_index: Option<Vec<String>>,
+
+    /// Separator placed between the values of the --multi-index columns.
+    #[arg(long, default_value_t = String::from(" "))]
+    pub multi_index_sep: String,
+
+    /// Only plot the rows matching this SQL condition, applied right after loading
+    /// (e.g., `"temperature > 100 AND status = 'active'"`). Quote strings with single quotes
+    /// and column names containing spaces with double quotes.
+    #[arg(long, value_name = "EXPR")]
+    pub filter: Option<String>,
+
+    /// Only plot the first N rows of each file, for a quick preview of large files.
+    #[arg(long, value_name = "N", conflicts_with = "tail")]
+    pub head: Option<usize>,
+
+    /// Only plot the last N rows of each file.
+    #[arg(long, value_name = "N")]
+    pub tail: Option<usize>,
+
+    /// Plot a random sample of N rows of each file instead of all of them, keeping the
+    /// original row order. Unlike downsampling, this suits categorical and unordered data.
+    #[arg(long, value_name = "N")]
+    pub sample: Option<usize>,
+
+    /// Random seed for --sample, so the same rows are chosen on every run.
+    #[arg(long, default_value_t = 42, requires = "sample")]
+    pub seed: u64,
+
+    /// Sort the rows by this column before plotting (and before downsampling), so lines
+    /// connect points in order. Use `_x` to sort by the selected X-axis column.
+    #[arg(long, value_name = "COLUMN")]
+    pub sort_by: Option<String>,
+
+    /// Sort in descending order with --sort-by.
+    #[arg(long, default_value_t = false, requires = "sort_by")]
+    pub sort_desc: bool,
+
+    /// Comma-separated list of columns to plot (Y-axis), given by name or by
+    /// zero-based column index (e.g., `0,temperature,5`).
     /// If not provided, all numeric columns will be plotted.
     #[arg(short = 'c', long, use_value_delimiter = true, value_delimiter = ',')]
     pub columns: Option<Vec<String>>,
 
+    /// Plot the columns whose names match this regular expression (e.g., `sensor_[0-9]+`)
+    /// instead of all numeric columns. The pattern may match anywhere in the name;
+    /// anchor it with `^...$` to match whole names.
+    #[arg(long, value_name = "PATTERN", value_parser = parse_regex, conflicts_with = "columns")]
+    pub columns_regex: Option<Regex>,
+
+    /// Comma-separated list of columns to leave out when plotting all numeric columns
+    /// (e.g., `debug_flag,raw_counter`). Every listed column must exist.
+    #[arg(
+        long,
+        use_value_delimiter = true,
+        value_delimiter = ',',
+        conflicts_with = "columns"
+    )]
+    pub exclude_columns: Vec<String>,
+
+    /// Rename columns before plotting, as a comma-separated list of `old:new` pairs
+    /// (e.g., `ch0:voltage,sig_1_filtered:current`). Column options such as --columns and
+    /// --index refer to the new names; --filter and --pivot-* refer to the original ones.
+    #[arg(long, value_name = "OLD:NEW", value_delimiter = ',', value_parser = parse_rename)]
+    pub rename: Vec<(String, String)>,
+
+    /// Fail instead of printing a warning when a column to --rename does not exist or
+    /// when a file has duplicate column names (which are otherwise renamed to `name_1`,
+    /// `name_2`, ...).
+    #[arg(long, default_value_t = false)]
+    pub strict: bool,
+
+    /// Add a computed column, given as `name=expression` (e.g., `power=voltage*current`).
+    /// Expressions combine column names and numbers with `+`, `-`, `*`, `/`, `**`, and
+    /// parentheses. Can be repeated; computed columns can be used as X or Y columns.
+    #[arg(long, value_name = "NAME=EXPR", value_parser = parse_expression_column)]
+    pub expression_column: Vec<(String, String)>,
+
+    /// Convert all datetime columns between time zones, given as `FROM:TO`
+    /// (e.g., `UTC:America/New_York`). Datetimes without a time zone are read as `FROM`
+    /// times; the plot shows the converted wall-clock times of `TO`.
+    #[arg(long, value_name = "FROM:TO", value_parser = parse_convert_tz)]
+    pub convert_tz: Option<(String, String)>,
+
+    /// Name of a categorical column whose values select the marker shape of each point.
+    /// Each Y column is split into one series per category.
+    #[arg(long)]
+    pub shape_column: Option<String>,
+
+    /// Name of a column whose values split each Y column into one series per group,
+    /// named `<column>/<group>`.
+    #[arg(long, conflicts_with = "shape_column")]
+    pub group_by: Option<String>,
+
+    /// Maximum number of groups plotted with --group-by; further groups are skipped.
+    #[arg(long, default_value_t = 20)]
+    pub max_groups: usize,
+
+    /// Order the series (and their legend entries) alphabetically by `name`, or by their
+    /// maximum Y value (`max`) or variance (`variance`) in descending order.
+    /// By default, series follow the column order of the data.
+    #[arg(long, value_parser = ["name", "max", "variance"])]
+    pub sort_series_by: Option<String>,
+
+    /// Draw a pie chart with one slice per Y column instead of a scatter plot.
+    /// Slice values come from each column's last value, or from --pie-agg.
+    #[arg(long, default_value_t = false)]
+    pub pie: bool,
+
+    /// How each column is reduced to a single slice value in pie mode.
+    #[arg(long, default_value = "last", value_parser = ["last", "sum", "mean"])]
+    pub pie_agg: String,
+
+    /// Draw a radar chart with one spoke per Y column and one polygon per row.
+    /// The X column values label the rows in the legend.
+    #[arg(long, default_value_t = false, conflicts_with = "pie")]
+    pub radar: bool,
+
+    /// Maximum number of rows drawn in radar mode.
+    #[arg(long, default_value_t = 5)]
+    pub radar_max_series: usize,
+
+    /// Draw a box-and-whisker plot with one box per Y column instead of a scatter plot.
+    /// Whiskers extend to the most extreme values within 1.5 IQR of the quartiles.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["pie", "radar"])]
+    pub boxplot: bool,
+
+    /// Draw a 2D density heatmap instead of a scatter plot: the (x, y) pairs are binned
+    /// into a grid and each cell is colored by its number of points.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["pie", "radar", "boxplot"])]
+    pub heatmap_2d: bool,
+
+    /// Number of X-axis bins in --heatmap-2d mode.
+    #[arg(long, default_value_t = 50)]
+    pub heatmap_bins_x: usize,
+
+    /// Number of Y-axis bins in --heatmap-2d mode.
+    #[arg(long, default_value_t = 50)]
+    pub heatmap_bins_y: usize,
+
+    /// Column binned along the X-axis in --heatmap-2d mode. Defaults to the X-axis column.
+    #[arg(long)]
+    pub heatmap_x_col: Option<String>,
+
+    /// Column binned along the Y-axis in --heatmap-2d mode. Defaults to the first Y column.
+    #[arg(long)]
+    pub heatmap_y_col: Option<String>,
+
+    /// Plot the empirical cumulative distribution function (ECDF) of each Y column
+    /// as a step line, ignoring the X column.
+    #[arg(long, default_value_t = false)]
+    pub ecdf: bool,
+
+    /// Add a smoothed trend line for each series: the mean of the last N points, drawn as a
+    /// line named `<column>_ma<N>` next to the raw points.
+    #[arg(long, value_name = "N")]
+    pub rolling_mean: Option<usize>,
+
+    /// Draw a lag plot of each Y column, plotting `y[t]` against `y[t + N]` (N defaults to 1).
+    /// The X column is ignored.
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "1", conflicts_with = "ecdf")]
+    pub lag_plot: Option<usize>,
+
+    /// Pivot a long-format DataFrame to wide format before plotting, with one column per
+    /// unique value of --pivot-column. Requires --pivot-index, --pivot-column, and --pivot-value.
+    #[arg(long, default_value_t = false, requires_all = ["pivot_index", "pivot_column", "pivot_value"])]
+    pub pivot: bool,
+
+    /// Column identifying the rows of the pivoted DataFrame (e.g., a timestamp).
+    /// It is used as the X-axis unless --index is given.
+    #[arg(long, requires = "pivot")]
+    pub pivot_index: Option<String>,
+
+    /// Column whose unique values become the columns of the pivoted DataFrame (e.g., a sensor ID).
+    #[arg(long, requires = "pivot")]
+    pub pivot_column: Option<String>,
+
+    /// Column holding the values of the pivoted DataFrame.
+    #[arg(long, requires = "pivot")]
+    pub pivot_value: Option<String>,
+
     /// A custom title for the plot.
     /// Defaults to the input filename.
     #[arg(short = 't', long)]
     pub title: Option<String>,
 
+    /// Use the first non-empty value of this column (e.g., a device name) as the plot
+    /// title, falling back to the input filename. The column is not plotted.
+    #[arg(long, value_name = "COLUMN", conflicts_with = "title")]
+    pub title_from_column: Option<String>,
+
+    /// Title text for the X-axis.
+    #[arg(long)]
+    pub x_axis_label: Option<String>,
+
+    /// Title text for the Y-axis.
+    #[arg(long)]
+    pub y_axis_label: Option<String>,
+
+    /// Unit of the X-axis values, shown in parentheses after the X-axis label.
+    #[arg(long)]
+    pub x_unit: Option<String>,
+
+    /// Unit of the Y-axis values, shown in parentheses after the Y-axis label.
+    #[arg(long)]
+    pub y_unit: Option<String>,
+
+    /// Approximate number of intervals between X-axis ticks.
+    /// Defaults to the ECharts automatic tick calculation.
+    #[arg(long)]
+    pub x_tick_count: Option<usize>,
+
+    /// Approximate number of intervals between Y-axis ticks.
+    /// Defaults to the ECharts automatic tick calculation.
+    #[arg(long)]
+    pub y_tick_count: Option<usize>,
+
+    /// Fixed lower bound of the X-axis, for consistent ranges across plots.
+    /// Datetime axes accept ISO 8601 values, like --mark-x.
+    #[arg(long, value_name = "POSITION", value_parser = parse_x_position, allow_negative_numbers = true)]
+    pub x_min: Option<f64>,
+
+    /// Fixed upper bound of the X-axis. Datetime axes accept ISO 8601 values, like --mark-x.
+    #[arg(long, value_name = "POSITION", value_parser = parse_x_position, allow_negative_numbers = true)]
+    pub x_max: Option<f64>,
+
+    /// Fixed lower bound of the Y-axis, replacing the padded data range.
+    /// Disables Y-axis autoscaling on zoom.
+    #[arg(long, allow_negative_numbers = true)]
+    pub y_min: Option<f64>,
+
+    /// Fixed upper bound of the Y-axis, replacing the padded data range.
+    /// Disables Y-axis autoscaling on zoom.
+    #[arg(long, allow_negative_numbers = true)]
+    pub y_max: Option<f64>,
+
+    /// Flip the X-axis so values increase from right to left.
+    #[arg(long, default_value_t = false)]
+    pub invert_x: bool,
+
+    /// Flip the Y-axis so values increase downwards (e.g., for depths).
+    #[arg(long, default_value_t = false)]
+    pub invert_y: bool,
+
+    /// Use a logarithmic scale for a numeric X-axis.
+    #[arg(long, default_value_t = false)]
+    pub log_x: bool,
+
+    /// Use a logarithmic scale for the Y-axis. Disables Y-axis autoscaling.
+    #[arg(long, default_value_t = false)]
+    pub log_y: bool,
+
+    /// Logarithm base of the X-axis with --log-x (e.g., 2 for octaves, 2.718281828 for ln).
+    #[arg(long, default_value_t = 10.0, value_parser = parse_log_base)]
+    pub x_log_base: f64,
+
+    /// Logarithm base of the Y-axis with --log-y.
+    #[arg(long, default_value_t = 10.0, value_parser = parse_log_base)]
+    pub y_log_base: f64,
+
+    /// Aggregate each series into regular X-axis buckets of the given length, using the
+    /// mean of each bucket (e.g., `500ms`, `30s`, `1min`, `2h`, `1d`, `1w`).
+    /// For non-time X-axes, the length is in X units (milliseconds for `ms`).
+    #[arg(long, value_name = "EVERY", value_parser = parse_resample_interval)]
+    pub resample: Option<i64>,
+
+    /// Forward-fill empty buckets with the previous bucket's value after --resample.
+    #[arg(long, default_value_t = false)]
+    pub resample_ffill: bool,
+
+    /// Backward-fill empty buckets with the next bucket's value after --resample.
+    /// Applied after --resample-ffill if both are given.
+    #[arg(long, default_value_t = false)]
+    pub resample_bfill: bool,
+
     /// Downsample series with more than N points using the LTTB algorithm to preserve visual features.
     /// If not provided, no downsampling is performed.
     #[arg(short = 'd', long = "downsample-threshold", default_value_t = 10000)]
     pub downsample_threshold: usize,
 
+    /// Treat NaN values in float Y columns as missing values, so they are not plotted.
+    #[arg(long, default_value_t = false)]
+    pub nan_to_null: bool,
+
+    /// Treat infinite values in float Y columns as missing values, so they are not plotted.
+    /// Can be combined with --nan-to-null.
+    #[arg(long, default_value_t = false)]
+    pub inf_to_null: bool,
+
+    /// Plot the first-order difference of each Y series (the change from the previous
+    /// row) instead of its values, as `<name>_diff`. The first row, which has no
+    /// predecessor, is dropped; with --group-by, differences are taken within each group
+    /// and each group's first row is dropped. Options naming a column, such as
+    /// --right-axis, still apply. Applied before --normalize, --z-score, and --rolling-mean.
+    #[arg(long, default_value_t = false)]
+    pub diff: bool,
+
+    /// Plot the cumulative sum of each Y series (the running total up to each row)
+    /// instead of its values, as `<name>_cumsum`. Missing values are skipped, and with
+    /// --group-by each group has its own running total. Options naming a column still
+    /// apply. Applied after --diff and before --normalize; combining it with --diff mostly
+    /// undoes the difference, so a warning is printed if both are given.
+    #[arg(long, default_value_t = false)]
+    pub cumsum: bool,
+
+    /// Plot the absolute value (magnitude) of each Y series, e.g. for signed sensor
+    /// data or audio samples. Applied after --diff and --cumsum and before --normalize.
+    #[arg(long, default_value_t = false)]
+    pub abs: bool,
+
+    /// Scale each Y series independently to the [0, 1] range, so that series with
+    /// very different magnitudes can be compared on a shared Y-axis.
+    #[arg(long, default_value_t = false)]
+    pub normalize: bool,
+
+    /// Standardize each Y series independently by subtracting its mean and dividing
+    /// by its sample standard deviation, to compare distributions and spot outliers.
+    #[arg(long, default_value_t = false, conflicts_with = "normalize")]
+    pub z_score: bool,
+
+    /// Remove points whose X value is in the bottom or top P percent of each series
+    /// (after downsampling), e.g. to drop timestamps far off due to clock skew.
+    #[arg(long, value_name = "P", value_parser = parse_clip_percent)]
+    pub clip_x: Option<f64>,
+
     /// Disable dynamic Y-axis autoscaling on zoom.
     /// When disabled, the Y-axis keeps its initial, globally-padded range.
     #[arg(short = 'n', long, default_value_t = false)]
     pub no_autoscale_y: bool,
 
+    /// Disable the interactive zoom (mouse wheel, slider, and toolbox zoom).
+    /// Also disables Y-axis autoscaling, which only reacts to zoom changes.
+    #[arg(long, default_value_t = false)]
+    pub no_data_zoom: bool,
+
+    /// Do not remember the zoom window across page reloads.
+    /// By default, the zoom window is saved in the browser's local storage, keyed by the plot title.
+    #[arg(long, default_value_t = false)]
+    pub no_persist_zoom: bool,
+
+    /// Hide the legend.
+    #[arg(long, default_value_t = false)]
+    pub no_legend: bool,
+
+    /// Append the time elapsed since the last data point (e.g., "[last point: 5m 23s ago]")
+    /// to the plot title. The elapsed time is computed when the page is opened.
+    /// Requires a datetime X-axis.
+    #[arg(long, default_value_t = false)]
+    pub show_time_since_last: bool,
+
+    /// Show the tooltip on click instead of on hover, and keep a crosshair with the X/Y values
+    /// at the clicked position until Escape is pressed.
+    #[arg(long, default_value_t = false)]
+    pub sticky_tooltip: bool,
+
+    /// Disable the keyboard shortcuts of the HTML plot (r: reset zoom, l: toggle legend,
+    /// g: toggle grid lines, d: toggle dark/white theme, s: save as image).
+    #[arg(long, default_value_t = false)]
+    pub no_keyboard_shortcuts: bool,
+
+    /// Append the number of points of each series to its legend entry, e.g. `temp (4892 pts)`.
+    /// Counts are taken after downsampling.
+    #[arg(long, default_value_t = false)]
+    pub show_n_points: bool,
+
+    /// Hide the series whose names match this glob pattern (e.g., `col_*`) until they are
+    /// toggled on in the legend. `*` matches any run of characters and `?` a single character.
+    /// Can be given several times.
+    #[arg(long, value_name = "PATTERN")]
+    pub default_hidden: Vec<String>,
+
+    /// Do not show the text box for filtering the legend by series name.
+    #[arg(long, default_value_t = false)]
+    pub no_series_search: bool,
+
+    /// Automatically hide the legend when only one series is plotted.
+    /// Pass `--auto-hide-legend false` to always show it.
+    #[arg(long, default_value_t = true, action = ArgAction::Set)]
+    pub auto_hide_legend: bool,
+
+    /// Make points in dense regions more transparent, so both dense and sparse regions stay visible.
+    /// Each point's opacity is inversely proportional to the number of neighboring points.
+    #[arg(long, default_value_t = false)]
+    pub scatter_opacity_by_density: bool,
+
+    /// Connect the points of each series with lines instead of drawing a plain scatter plot.
+    #[arg(long, default_value_t = false)]
+    pub line: bool,
+
+    /// How each series is drawn: `scatter` points, a `line`, `bar`s, or a `step` line
+    /// (stepping at the end of each interval unless --step says otherwise).
+    /// `line` and `step` are equivalent to --line and --step end.
+    #[arg(long, default_value = "scatter", value_parser = ["scatter", "line", "bar", "step"])]
+    pub chart_type: String,
+
+    /// Draw lines as a step function, with the step placed at the start, middle, or end
+    /// of each interval. Implies --line.
+    #[arg(long, value_parser = ["start", "middle", "end"])]
+    pub step: Option<String>,
+
+    /// Stack all series on top of each other under the given stack group name.
+    /// Combined with --line, this produces a stacked area chart.
+    #[arg(long)]
+    pub stack: Option<String>,
+
+    /// Line thickness (in pixels) for all series in line mode.
+    #[arg(long, default_value_t = 1.5)]
+    pub line_width: f64,
+
+    /// Per-series line thickness overrides in line mode, as a comma-separated list of
+    /// `column:width` pairs (e.g., `temp:2.0,rpm:0.5`).
+    #[arg(long, value_delimiter = ',', value_parser = parse_series_line_width)]
+    pub series_line_width: Vec<(String, f64)>,
+
     /// Enable ECharts animations for a more dynamic feel.
     /// Animations are disabled by default for performance.
     #[arg(short = 'a', long, default_value_t = false)]
@@ -69,10 +491,232 @@ pub struct Cli {
     #[arg(short = 'M', long = "special-marker", default_value_t = String::from("|"))]
     pub vertical_marker: String,
 
+    /// Color of the vertical marker lines (e.g., "#c23531").
+    #[arg(long, default_value_t = String::from("#c23531"))]
+    pub marker_color: String,
+
+    /// Opacity of the vertical marker lines, from 0.0 (invisible) to 1.0 (opaque).
+    #[arg(long, default_value_t = 1.0)]
+    pub marker_opacity: f64,
+
+    /// Width (in pixels) of the vertical marker lines.
+    #[arg(long, default_value_t = 2.0)]
+    pub marker_width: f64,
+
     /// Threshold for ECharts `large` mode. Series with more points than this will be optimized for performance, which may reduce detail.
     #[arg(short = 'l', long = "large-mode-threshold", default_value_t = 2000)]
     pub large_mode_threshold: usize,
 
+    /// Color palette used for the plotted series.
+    #[arg(long, default_value = "default", value_parser = ["default", "colorblind"])]
+    pub palette: String,
+
+    /// Use the colorblind-safe Okabe-Ito palette and alternate marker shapes between series.
+    /// Shortcut for `--palette colorblind`.
+    #[arg(long, default_value_t = false)]
+    pub colorblind_safe: bool,
+
+    /// Base font size (in pixels) for all text in the plot.
+    /// Defaults to the ECharts default size.
+    #[arg(long)]
+    pub font_size: Option<u32>,
+
+    /// Font family for all text in the plot (e.g., "Arial, sans-serif").
+    /// Defaults to the ECharts default font.
+    #[arg(long)]
+    pub font_family: Option<String>,
+
+    /// Left margin of the plot area (e.g., "60px" or "10%").
+    /// Defaults to a width estimated from the longest series name.
+    #[arg(long)]
+    pub grid_left: Option<String>,
+
+    /// Right margin of the plot area (e.g., "60px" or "10%").
+    #[arg(long)]
+    pub grid_right: Option<String>,
+
+    /// Top margin of the plot area (e.g., "60px" or "10%").
+    #[arg(long)]
+    pub grid_top: Option<String>,
+
+    /// Bottom margin of the plot area (e.g., "60px" or "10%").
+    #[arg(long)]
+    pub grid_bottom: Option<String>,
+
+    /// Give each series its own Y-axis with an independent scale.
+    /// Axes alternate between the left and right side; at most 6 axes are created.
+    #[arg(long, default_value_t = false)]
+    pub multi_yaxis: bool,
+
+    /// Plot the given series against a second Y-axis on the right side, as a comma-separated
+    /// list of series names (e.g., `pressure,humidity`), for series with different units.
+    /// The other series use the left Y-axis; each axis is scaled independently.
+    #[arg(
+        long,
+        value_name = "COL",
+        value_delimiter = ',',
+        conflicts_with = "multi_yaxis"
+    )]
+    pub right_axis: Vec<String>,
+
+    /// CSV file of X-axis regions to highlight, with columns `start`, `end`, `label`
+    /// and an optional `color` (defaults to a translucent grey).
+    /// Colors in the file must not contain commas (e.g., use "#ff000033").
+    #[arg(long)]
+    pub mark_regions: Option<PathBuf>,
+
+    /// Draw a vertical reference line at this X value. Can be given several times.
+    /// Datetime axes accept ISO 8601 values (e.g., `2024-01-01` or `2024-01-01T12:00:00`)
+    /// as well as milliseconds since the epoch.
+    #[arg(long, value_name = "POSITION", value_parser = parse_x_position, allow_negative_numbers = true)]
+    pub mark_x: Vec<f64>,
+
+    /// Draw a horizontal reference line at this Y value (e.g., an alarm threshold).
+    /// Can be given several times.
+    #[arg(long, value_name = "VALUE", allow_negative_numbers = true)]
+    pub mark_y: Vec<f64>,
+
+    /// Draw the --mark-y lines on every series instead of only the first one.
+    /// Useful with --multi-yaxis, where each series has its own Y scale.
+    #[arg(long, default_value_t = false)]
+    pub mark_applies_to_all: bool,
+
+    /// Zero-based index of the only audio channel to load and plot.
+    /// Defaults to all channels; ignored for non-audio files.
+    #[arg(long)]
+    pub audio_channel: Option<usize>,
+
+    /// Print audio metadata (sample rate, bit depth, channels, codec, and duration)
+    /// for each audio file. Always enabled in debug mode.
+    #[arg(long, visible_alias = "audio-info", default_value_t = false)]
+    pub show_sample_rate: bool,
+
+    /// Plot the magnitude spectrum of each audio channel against frequency (in Hz)
+    /// instead of the waveform. Ignored for non-audio files.
+    #[arg(long, default_value_t = false)]
+    pub fft: bool,
+
+    /// Plot audio files against time in seconds instead of the sample index.
+    /// Ignored for non-audio files.
+    #[arg(long, default_value_t = false, conflicts_with = "fft")]
+    pub audio_time_axis: bool,
+
+    /// Only load the given time range of audio files, as `START:END` in seconds (e.g., `60.5:90.0`).
+    /// Decoding starts by seeking to START, so long recordings are not decoded in full.
+    #[arg(long, value_name = "START:END", value_parser = parse_clip_audio)]
+    pub clip_audio: Option<(f64, f64)>,
+
+    /// Skip audio packets that fail to decode instead of aborting, printing a warning for each.
+    #[arg(long, default_value_t = false)]
+    pub audio_skip_errors: bool,
+
+    /// Maximum number of corrupt audio packets skipped with --audio-skip-errors before
+    /// giving up on the file.
+    #[arg(long, default_value_t = 100)]
+    pub audio_max_errors: usize,
+
+    /// Split the data into chunks of N rows and save one plot per chunk, named
+    /// `<stem>_chunk_0000.html`, `<stem>_chunk_0001.html`, and so on.
+    #[arg(long, value_name = "N", conflicts_with = "append")]
+    pub chunk_size: Option<usize>,
+
+    /// Number of rows shared by consecutive chunks with --chunk-size, for continuity
+    /// across chunk boundaries.
+    #[arg(long, default_value_t = 0, requires = "chunk_size")]
+    pub chunk_overlap: usize,
+
+    /// Warn when a generated HTML file is larger than this many megabytes.
+    #[arg(long, default_value_t = 50.0)]
+    pub warn_size_mb: f64,
+
+    /// Also write a `<stem>.summary.json` file next to each plot, with its input and
+    /// output file names, its series and their point counts, the X and Y ranges, and
+    /// whether it was downsampled.
+    #[arg(long, default_value_t = false, conflicts_with = "combine")]
+    pub json_summary: bool,
+
+    /// Plot the series of all input files together in one HTML page, named `combined.html`
+    /// unless an output file is given. Series are prefixed with the stem of their file in
+    /// the legend (e.g., `sensor_a: temperature`).
+    #[arg(
+        short = 'C',
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["pie", "radar", "boxplot", "heatmap_2d", "chunk_size", "append", "print_stats"]
+    )]
+    pub combine: bool,
+
+    /// After all files are processed, write an `index.html` linking to every generated plot,
+    /// with each input's row and column count. It is saved in the output directory, or in
+    /// the input folder if plots are saved next to their inputs.
+    #[arg(long, default_value_t = false)]
+    pub index_page: bool,
+
+    /// Number of files to process in parallel when the input is a directory.
+    /// Defaults to the number of CPU cores; files are processed one at a time with
+    /// `--print-stats` or `--list-sheets`, so their output stays in input order.
+    #[arg(short = 'j', long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+
+    /// Keep processing the remaining files when one fails, printing its error and a count
+    /// of failed files at the end. The exit code is then 0 even if some files failed.
+    #[arg(long, default_value_t = false, overrides_with = "fail_on_any_error")]
+    pub continue_on_error: bool,
+
+    /// Stop at the first file that fails and exit with an error (the default).
+    /// Overrides an earlier --continue-on-error.
+    #[arg(long, default_value_t = false, overrides_with = "continue_on_error")]
+    pub fail_on_any_error: bool,
+
+    /// Update an existing output plot instead of replacing it: keep its data and add only
+    /// the rows whose X value is newer than its last point.
+    #[arg(long, default_value_t = false)]
+    pub append: bool,
+
+    /// Print summary statistics for each column as markdown tables instead of generating a plot:
+    /// count, nulls, mean, std, min, quartiles, and max for numeric columns, and unique count,
+    /// mode, and most frequent values for string columns.
+    #[arg(long, default_value_t = false)]
+    pub print_stats: bool,
+
+    /// Print the --print-stats summary as JSON instead of a markdown table: one object
+    /// keyed by input path, with status messages moved to stderr so stdout is valid JSON.
+    #[arg(long, default_value_t = false, requires = "print_stats")]
+    pub json: bool,
+
+    /// Print the minimum and maximum of the X-axis values before plotting.
+    /// Always enabled in debug mode.
+    #[arg(long, default_value_t = false)]
+    pub print_x_range: bool,
+
+    /// Field delimiter of CSV-like files, as a single character (e.g., `;`, `|`, or `\t` for tabs).
+    /// Defaults to a tab for `.tsv` and `.tab` files and to a comma otherwise.
+    #[arg(long, value_parser = parse_delimiter)]
+    pub delimiter: Option<u8>,
+
+    /// Layout of JSON files: an `array` of objects, JSON `lines` (one object per line), or
+    /// `auto` to detect it from the first non-whitespace character (`[` for an array).
+    #[arg(long, default_value = "auto", value_parser = ["auto", "array", "lines"])]
+    pub json_format: String,
+
+    /// Worksheet to read from Excel files, by name or zero-based index. Defaults to the first sheet.
+    #[arg(long, value_parser = parse_sheet)]
+    pub sheet: Option<SheetSelector>,
+
+    /// Print the worksheet names of each Excel file instead of generating a plot.
+    #[arg(long, default_value_t = false)]
+    pub list_sheets: bool,
+
+    /// Format of the data read from stdin (input path `-`). Defaults to CSV.
+    #[arg(long, value_parser = ["csv", "tsv", "parquet", "arrow", "json"])]
+    pub format: Option<String>,
+
+    /// Set the type of columns instead of inferring it, as a comma-separated list of
+    /// `column:type` pairs (e.g., `id:string,timestamp:datetime,value:float64`).
+    /// Types are `string`, `float64`, `int64`, `datetime`, `date`, and `bool`.
+    #[arg(long, value_name = "COL:TYPE", value_delimiter = ',', value_parser = parse_column_type)]
+    pub column_types: Vec<(String, DataType)>,
+
     /// Print debug information during processing.
     /// This includes detected columns, data types, and DataFrame shape.
     #[arg(short = 'D', long, default_value_t = false)]
@@ -81,4 +725,205 @@ pub struct Cli {
     /// Use a white (light) theme for the plot instead of the default dark theme.
     #[arg(short = 'w', long = "white-theme", default_value_t = false)]
     pub white_theme: bool,
+
+    /// Follow the OS dark/light preference instead of always using the dark theme.
+    /// The plot switches theme when the preference changes while the page is open.
+    #[arg(long, default_value_t = false, conflicts_with = "white_theme")]
+    pub dark_mode_auto: bool,
+
+    /// Embed the ECharts library in each HTML file instead of loading it from a CDN,
+    /// so that plots open without internet access. Makes each file about 1 MB larger.
+    /// Requires a build with the `embed-echarts` feature.
+    #[arg(long, visible_alias = "embed-js", default_value_t = false)]
+    pub offline: bool,
+}
+
+/// Parses a `column:width` pair for `--series-line-width`.
+///
+/// The value is split on the last `:`, so column names may themselves contain colons.
+fn parse_series_line_width(s: &str) -> Result<(String, f64), String> {
+    let (name, width) = s
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected `column:width`, got '{}'", s))?;
+    let width = width
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("invalid line width '{}' for column '{}'", width, name))?;
+    Ok((name.trim().to_string(), width))
+}
+
+/// Parses an `old:new` column name pair for `--rename`.
+fn parse_rename(s: &str) -> Result<(String, String), String> {
+    let (old, new) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected `old:new`, got '{}'", s))?;
+    let (old, new) = (old.trim(), new.trim());
+    if old.is_empty() || new.is_empty() {
+        return Err(format!("expected `old:new`, got '{}'", s));
+    }
+    Ok((old.to_string(), new.to_string()))
+}
+
+/// Parses a `column:type` pair for `--column-types`.
+///
+/// The value is split on the last `:`, so column names may themselves contain colons.
+fn parse_column_type(s: &str) -> Result<(String, DataType), String> {
+    let (column, dtype) = s
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected `column:type`, got '{}'", s))?;
+    let column = column.trim();
+    if column.is_empty() {
+        return Err(format!("missing column name in '{}'", s));
+    }
+    let dtype = parse_dtype(dtype).map_err(|e| e.to_string())?;
+    Ok((column.to_string(), dtype))
+}
+
+/// Parses a `name=expression` pair for `--expression-column`.
+///
+/// The value is split on the first `=`; the expression itself is validated when evaluated.
+fn parse_expression_column(s: &str) -> Result<(String, String), String> {
+    let (name, expression) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `name=expression`, got '{}'", s))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing column name in '{}'", s));
+    }
+    Ok((name.to_string(), expression.trim().to_string()))
+}
+
+/// Parses a `FROM:TO` time zone pair for `--convert-tz`.
+///
+/// The value is split on the first `:`, so only the target may be a fixed offset
+/// such as `+05:30`.
+fn parse_convert_tz(s: &str) -> Result<(String, String), String> {
+    let (from, to) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected `FROM:TO`, got '{}'", s))?;
+    Ok((from.trim().to_string(), to.trim().to_string()))
+}
+
+/// Parses a `START:END` time range in seconds for `--clip-audio`.
+fn parse_clip_audio(s: &str) -> Result<(f64, f64), String> {
+    let (start, end) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected `START:END`, got '{}'", s))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid time '{}' in '{}'", v, s))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start < 0.0 || end <= start {
+        return Err(format!(
+            "END must be greater than START (>= 0), got '{}'",
+            s
+        ));
+    }
+    Ok((start, end))
+}
+
+/// Parses a `--resample` interval such as `30s` or `1min` into milliseconds.
+fn parse_resample_interval(s: &str) -> Result<i64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("missing unit in interval '{}' (e.g., `30s` or `1min`)", s))?;
+    let (amount, unit) = s.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid interval '{}'", s))?;
+    let unit_ms = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" | "min" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        _ => return Err(format!("unknown unit '{}' in interval '{}'", unit, s)),
+    };
+    if amount <= 0 {
+        return Err(format!("interval '{}' must be positive", s));
+    }
+    Ok(amount * unit_ms)
+}
+
+/// Parses a logarithm base for `--x-log-base` and `--y-log-base`, which must be
+/// positive and different from 1.
+fn parse_log_base(s: &str) -> Result<f64, String> {
+    let base: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", s))?;
+    if !(base > 0.0 && base.is_finite()) || base == 1.0 {
+        return Err(format!("the base must be positive and not 1, got '{}'", s));
+    }
+    Ok(base)
+}
+
+/// Parses an X-axis position (`--mark-x`, `--x-min`, `--x-max`): a number, or an ISO 8601
+/// date or datetime, which is converted to milliseconds since the epoch (UTC unless an
+/// offset is given).
+fn parse_x_position(s: &str) -> Result<f64, String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+    let s = s.trim();
+    if let Ok(value) = s.parse::<f64>() {
+        return Ok(value);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.timestamp_millis() as f64);
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(dt.and_utc().timestamp_millis() as f64);
+        }
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis() as f64)
+        .ok_or_else(|| format!("expected a number or an ISO 8601 datetime, got '{}'", s))
+}
+
+/// Parses a `--clip-x` percentage, which must be in `[0, 50)`.
+fn parse_clip_percent(s: &str) -> Result<f64, String> {
+    let p: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", s))?;
+    if !(0.0..50.0).contains(&p) {
+        return Err(format!("the percentage must be in [0, 50), got '{}'", s));
+    }
+    Ok(p)
+}
+
+/// Parses a single-byte `--delimiter`, accepting `\t` or `tab` for a tab character.
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 => Ok(s.as_bytes()[0]),
+        _ => Err(format!(
+            "expected a single ASCII character or `\\t`, got '{}'",
+            s
+        )),
+    }
+}
+
+/// Parses a `--sheet` selector: a zero-based index if it is a number, and a name otherwise.
+fn parse_sheet(s: &str) -> Result<SheetSelector, String> {
+    Ok(match s.parse::<usize>() {
+        Ok(index) => SheetSelector::Index(index),
+        Err(_) => SheetSelector::Name(s.to_string()),
+    })
+}
+
+/// Parses a `--columns-regex` pattern, reporting syntax errors at startup.
+fn parse_regex(s: &str) -> Result<Regex, String> {
+    Regex::new(s).map_err(|e| format!("invalid regular expression: {}", e))
 }
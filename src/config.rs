@@ -0,0 +1,164 @@
+//! Default command-line options read from a TOML config file.
+//!
+//! The config file is taken from `--config`, or else from `.scatters.toml` in the
+//! current directory or `~/.config/scatters/config.toml`, whichever exists first.
+//! Its keys are the long option names, with dashes or underscores, for example:
+//!
+//! ```toml
+//! white-theme = true
+//! downsample-threshold = 5000
+//! max_decimals = 4
+//! mark-y = [10, 20]
+//! ```
+//!
+//! Options given on the command line take precedence over the config file.
+
+use crate::cli::Cli;
+use crate::error::AppError;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, Command, CommandFactory, FromArgMatches};
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the config file looked up in the current directory.
+const LOCAL_CONFIG_FILE: &str = ".scatters.toml";
+
+/// Default option values read from a config file.
+#[derive(Deserialize, Debug, Default)]
+pub struct Config {
+    /// Option values by name; each is applied as if given on the command line.
+    #[serde(flatten)]
+    pub options: toml::Table,
+}
+
+impl Config {
+    /// Reads and parses a config file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::InvalidConfig` if the file is not valid TOML.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let text = fs::read_to_string(path)?;
+        toml::from_str(&text)
+            .map_err(|e| AppError::InvalidConfig(path.to_path_buf(), e.to_string()))
+    }
+
+    /// Converts the options into command-line arguments, leaving out those given on the
+    /// command line (`matches`) or conflicting with them.
+    ///
+    /// `true` flags become `--name`, `false` flags are left out, arrays repeat the option,
+    /// and other values become `--name=value`.
+    fn to_args(
+        &self,
+        command: &Command,
+        matches: &ArgMatches,
+        path: &Path,
+    ) -> Result<Vec<OsString>, AppError> {
+        let invalid = |message: String| AppError::InvalidConfig(path.to_path_buf(), message);
+        let on_command_line = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+        let mut args = Vec::new();
+        for (key, value) in &self.options {
+            let id = key.replace('-', "_");
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_id() == id.as_str())
+                .filter(|arg| arg.get_long().is_some() && id != "config")
+                .ok_or_else(|| invalid(format!("unknown option '{}'", key)))?;
+            // Conflicts may be declared on either argument.
+            let conflicts = |a: &Arg, b: &Arg| {
+                command
+                    .get_arg_conflicts_with(a)
+                    .iter()
+                    .any(|c| c.get_id() == b.get_id())
+            };
+            let overridden = on_command_line(&id)
+                || command
+                    .get_arguments()
+                    .filter(|other| on_command_line(other.get_id().as_str()))
+                    .any(|other| conflicts(arg, other) || conflicts(other, arg));
+            if overridden {
+                continue;
+            }
+
+            let long = format!("--{}", arg.get_long().unwrap_or_default());
+            let values = match value {
+                toml::Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            };
+            for value in values {
+                let text = match value {
+                    toml::Value::Boolean(true) if !arg.get_action().takes_values() => {
+                        args.push(OsString::from(&long));
+                        continue;
+                    }
+                    toml::Value::Boolean(false) if !arg.get_action().takes_values() => continue,
+                    toml::Value::String(s) => s.clone(),
+                    toml::Value::Integer(i) => i.to_string(),
+                    toml::Value::Float(f) => f.to_string(),
+                    toml::Value::Boolean(b) => b.to_string(),
+                    _ => return Err(invalid(format!("unsupported value for '{}'", key))),
+                };
+                args.push(OsString::from(format!("{}={}", long, text)));
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Returns the `Cli` command extended with `--config`.
+///
+/// `--config` is only needed to locate the config file, so it is declared here rather
+/// than as a `Cli` field. Use this command for help and shell completions.
+pub fn command() -> Command {
+    Cli::command().arg(
+        Arg::new("config")
+            .long("config")
+            .value_name("PATH")
+            .value_parser(clap::value_parser!(PathBuf))
+            .help(
+                "TOML file with default values for any of these options, keyed by their long \
+                 names (e.g., `white-theme = true`). Defaults to `.scatters.toml` in the current \
+                 directory or `~/.config/scatters/config.toml`. Command-line options take \
+                 precedence",
+            ),
+    )
+}
+
+/// Returns the config file to use: the `--config` path, or the first existing default file.
+fn config_path(matches: &ArgMatches) -> Option<PathBuf> {
+    if let Some(path) = matches.get_one::<PathBuf>("config") {
+        return Some(path.clone());
+    }
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    let user_config = home.map(|home| PathBuf::from(home).join(".config/scatters/config.toml"));
+    std::iter::once(PathBuf::from(LOCAL_CONFIG_FILE))
+        .chain(user_config)
+        .find(|path| path.is_file())
+}
+
+/// Parses the command-line arguments, filling in options that were not given from the
+/// config file, if any.
+///
+/// Usage errors and `--help` exit the process, like `Cli::parse`.
+///
+/// # Errors
+///
+/// Returns an error if the config file cannot be read or contains invalid options.
+pub fn parse_args_with_config(args: Vec<OsString>) -> Result<Cli, AppError> {
+    let command = command();
+    let matches = command.clone().get_matches_from(&args);
+    let Some(path) = config_path(&matches) else {
+        return Ok(Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit()));
+    };
+
+    let config_args = Config::load(&path)?.to_args(&command, &matches, &path)?;
+    let mut merged = args;
+    let rest = merged.split_off(1.min(merged.len()));
+    merged.extend(config_args);
+    merged.extend(rest);
+    let matches = command.get_matches_from(merged);
+    Ok(Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit()))
+}
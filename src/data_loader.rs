@@ -1,30 +1,141 @@
 //! This module handles loading data from various file formats into Polars DataFrames.
 //!
-//! It supports common tabular formats like CSV, Parquet, JSON Lines, and Excel,
-//! as well as audio formats like WAV, MP3, and FLAC. The module also includes
+//! It supports common tabular formats like CSV, Parquet, Arrow IPC (Feather), JSON Lines, and Excel,
+//! as well as audio formats like WAV, MP3, FLAC, OGG, M4A, and AAC. CSV, Parquet, and
+//! JSON files may also be gzip- or zstd-compressed (e.g., `data.csv.gz`). The module also includes
 //! logic for automatic type inference and casting, such as converting string columns
 //! that appear to be numeric or datetime values into their proper types.
 
-use crate::cli::Cli;
+use crate::cli::{Cli, SheetSelector};
 use crate::error::AppError;
 use calamine::{open_workbook_auto, Data, Reader};
+use polars::io::mmap::MmapBytesReader;
 use polars::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 use symphonia::core::audio::SampleBuffer;
 use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::FormatOptions;
+use symphonia::core::formats::{FormatOptions, SeekMode, SeekTo};
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
+use symphonia::core::units::{Time, TimeBase};
+
+/// Audio file extensions decoded with Symphonia.
+const AUDIO_EXTENSIONS: [&str; 6] = ["wav", "mp3", "flac", "ogg", "m4a", "aac"];
+
+/// Compression extensions that are decompressed transparently before reading.
+const COMPRESSION_EXTENSIONS: [&str; 2] = ["gz", "zst"];
+
+/// Formats that can be read from a compressed file, e.g. `data.csv.gz`.
+const COMPRESSIBLE_EXTENSIONS: [&str; 9] = [
+    "csv", "tsv", "tab", "parquet", "arrow", "feather", "json", "jsonl", "ndjson",
+];
+
+/// All file extensions (lowercase) that `load_dataframe` can read.
+///
+/// Compressed files (`gz`, `zst`) must also name a compressible format before the
+/// compression extension; use `is_supported_file` to check a path. `opus` is left out
+/// because Symphonia has no Opus decoder.
+pub const SUPPORTED_EXTENSIONS: [&str; 19] = [
+    "csv", "tsv", "tab", "parquet", "arrow", "feather", "json", "jsonl", "ndjson", "xlsx", "xls",
+    "wav", "mp3", "flac", "ogg", "m4a", "aac", "gz", "zst",
+];
+
+/// Input path that makes `load_dataframe` read from stdin instead of a file.
+pub const STDIN_PATH: &str = "-";
+
+/// Returns `true` if the path is the stdin sentinel `-`.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == STDIN_PATH
+}
+
+/// Splits a path into its lowercase format extension and optional compression extension,
+/// e.g. `data.csv.gz` gives `("csv", Some("gz"))` and `data.csv` gives `("csv", None)`.
+fn file_extensions(path: &Path) -> (String, Option<String>) {
+    let extension_of = |p: &Path| {
+        p.extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+    let extension = extension_of(path);
+    if COMPRESSION_EXTENSIONS.contains(&extension.as_str()) {
+        let inner = Path::new(path.file_stem().unwrap_or_default());
+        (extension_of(inner), Some(extension))
+    } else {
+        (extension, None)
+    }
+}
+
+/// Returns `true` if `load_dataframe` can read the file, judging by its extension(s).
+pub fn is_supported_file(path: &Path) -> bool {
+    match file_extensions(path) {
+        (format, Some(_)) => COMPRESSIBLE_EXTENSIONS.contains(&format.as_str()),
+        (format, None) => {
+            SUPPORTED_EXTENSIONS.contains(&format.as_str())
+                && !COMPRESSION_EXTENSIONS.contains(&format.as_str())
+        }
+    }
+}
+
+/// Returns `true` if the file is an audio file, judging by its extension.
+pub fn is_audio_file(path: &Path) -> bool {
+    matches!(file_extensions(path), (format, None) if AUDIO_EXTENSIONS.contains(&format.as_str()))
+}
+
+/// Returns the file name of a data file without its format and compression extensions,
+/// e.g. `data` for both `data.csv` and `data.csv.gz`.
+pub fn data_file_stem(path: &Path) -> String {
+    let stem = Path::new(path.file_stem().unwrap_or_default());
+    match file_extensions(path).1 {
+        Some(_) => stem.file_stem().unwrap_or_default(),
+        None => stem.as_os_str(),
+    }
+    .to_string_lossy()
+    .to_string()
+}
+
+/// Opens a file for reading, decompressing it on the fly if it ends in `.gz` or `.zst`.
+pub fn open_possibly_compressed(path: &Path) -> Result<Box<dyn Read>, AppError> {
+    let file = File::open(path)?;
+    Ok(match file_extensions(path).1.as_deref() {
+        Some("gz") => Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(file))),
+        Some("zst") => Box::new(zstd::stream::read::Decoder::new(file)?),
+        _ => Box::new(file),
+    })
+}
+
+/// Opens a file (or stdin, for the path `-`) for the Polars readers.
+///
+/// Plain files are passed through as-is so they can be memory-mapped; compressed files
+/// and stdin are read into memory first, since the readers need a seekable source.
+fn open_tabular_reader(path: &Path) -> Result<Box<dyn MmapBytesReader>, AppError> {
+    let mut bytes = Vec::new();
+    if is_stdin(path) {
+        std::io::stdin().lock().read_to_end(&mut bytes)?;
+        return Ok(Box::new(Cursor::new(bytes)));
+    }
+    if file_extensions(path).1.is_none() {
+        return Ok(Box::new(File::open(path)?));
+    }
+    open_possibly_compressed(path)?.read_to_end(&mut bytes)?;
+    Ok(Box::new(Cursor::new(bytes)))
+}
 
 /// Loads a supported file into a Polars DataFrame.
 ///
 /// This function inspects the file extension to determine the appropriate loader.
-/// After initial loading, it attempts to perform automatic type coercion:
+/// Compressed CSV, Parquet, Arrow IPC, and JSON files are decompressed transparently.
+/// After initial loading, columns listed in `--column-types` are cast to the given type,
+/// and the other columns go through automatic type coercion:
 /// 1.  String columns that look entirely numeric are cast to `Float64`.
 /// 2.  Remaining string columns that resemble datetime formats are cast to `Datetime`.
 ///
+/// Arrow IPC files carry their own schema, so only the `--column-types` casts are applied.
+/// The path `-` reads from stdin, in the format given by `--format` (CSV by default).
+///
 /// # Arguments
 ///
 /// * `path` - A reference to the path of the file to load.
@@ -34,78 +145,45 @@ use symphonia::core::meta::MetadataOptions;
 /// A `Result` containing the loaded `DataFrame` on success, or an `AppError`
 /// if the file format is unsupported, an I/O error occurs, or parsing fails.
 pub fn load_dataframe(path: &Path, cli: &Cli) -> Result<DataFrame, AppError> {
-    let extension = path
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or_default()
-        .to_lowercase();
+    let extension = if is_stdin(path) {
+        cli.format.clone().unwrap_or_else(|| "csv".to_string())
+    } else if is_supported_file(path) {
+        file_extensions(path).0
+    } else {
+        return Err(AppError::UnsupportedFormat(
+            path.to_string_lossy().to_string(),
+        ));
+    };
 
     let mut df = match extension.as_str() {
-        "csv" => {
-            // First read the file and clean up any broken records
-            let mut data = String::new();
-            File::open(path)?.read_to_string(&mut data)?;
-
-            // Split into lines
-            let lines: Vec<_> = data.lines().collect();
-            if lines.is_empty() {
-                return Err(AppError::Polars(PolarsError::NoData(
-                    "CSV file is empty".into(),
-                )));
-            }
-
-            // Get headers
-            let headers = lines[0]
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect::<Vec<_>>();
-            let col_count = headers.len();
-
-            // Create empty columns
-            let mut columns: Vec<Vec<Option<String>>> = vec![Vec::new(); col_count];
-
-            // Process each line
-            for line in lines.iter().skip(1) {
-                let fields: Vec<_> = line.split(',').map(|s| s.trim().to_string()).collect();
-
-                // Add each field to its column, padding with None if missing
-                for (i, column) in columns.iter_mut().enumerate() {
-                    let value = fields
-                        .get(i)
-                        .map(|field| {
-                            if field.is_empty() {
-                                None
-                            } else {
-                                Some(field.clone())
-                            }
-                        })
-                        .unwrap_or(None);
-                    column.push(value);
-                }
-            }
-
-            // Create Polars Series for each column
-            let mut series_vec = Vec::with_capacity(col_count);
-            for (i, name) in headers.iter().enumerate() {
-                let series = Series::new(name.as_str().into(), &columns[i]);
-                series_vec.push(series.into());
-            }
-
-            // Create DataFrame
-            DataFrame::new(series_vec).map_err(AppError::from)?
+        "csv" | "tsv" | "tab" => {
+            let default_separator = if extension == "csv" { b',' } else { b'\t' };
+            load_csv_dataframe(path, cli.delimiter.unwrap_or(default_separator), cli.strict)?
         }
-        "parquet" => ParquetReader::new(File::open(path)?)
+        "parquet" => ParquetReader::new(open_tabular_reader(path)?)
             .finish()
             .map_err(AppError::from)?,
+        "arrow" | "feather" => {
+            // The IPC schema is authoritative; skip the string coercion passes below.
+            let mut df = IpcReader::new(open_tabular_reader(path)?).finish()?;
+            apply_column_types(&mut df, cli)?;
+            df.rechunk_mut();
+            return Ok(df);
+        }
         "json" | "jsonl" | "ndjson" => {
-            let file = File::open(path)?;
-            JsonReader::new(file)
-                .with_json_format(JsonFormat::JsonLines)
+            let mut reader = open_tabular_reader(path)?;
+            let format = match cli.json_format.as_str() {
+                "array" => JsonFormat::Json,
+                "lines" => JsonFormat::JsonLines,
+                _ => detect_json_format(&mut reader)?,
+            };
+            JsonReader::new(reader)
+                .with_json_format(format)
                 .finish()
                 .map_err(AppError::from)?
         }
-        "xlsx" | "xls" => load_excel_dataframe(path)?,
-        "wav" | "mp3" | "flac" | "ogg" | "m4a" | "aac" => return load_audio_dataframe(path),
+        "xlsx" | "xls" => load_excel_dataframe(path, cli.sheet.as_ref(), cli.strict)?,
+        ext if AUDIO_EXTENSIONS.contains(&ext) => return load_audio_dataframe(path, cli),
         _ => {
             return Err(AppError::UnsupportedFormat(
                 path.to_string_lossy().to_string(),
@@ -113,11 +191,13 @@ pub fn load_dataframe(path: &Path, cli: &Cli) -> Result<DataFrame, AppError> {
         }
     };
 
+    // Columns with an explicit type are cast first and skipped by the passes below.
+    apply_column_types(&mut df, cli)?;
     // First, try to coerce string columns that look numeric into Float64.
     // This prevents purely numeric IDs from being misinterpreted as dates.
     try_cast_string_columns_to_numeric(&mut df, cli)?;
     // Next, attempt to auto-coerce remaining string columns that look like datetimes.
-    try_cast_string_columns_to_datetime(&mut df)?;
+    try_cast_string_columns_to_datetime(&mut df, cli)?;
     // After all in-place modifications, rechunk the DataFrame to ensure
     // all columns have a single, contiguous memory layout. This prevents
     // iterator panics when zipping columns with different chunk counts.
@@ -125,6 +205,215 @@ pub fn load_dataframe(path: &Path, cli: &Cli) -> Result<DataFrame, AppError> {
     Ok(df)
 }
 
+/// Number of bytes inspected by `detect_json_format`.
+const JSON_SNIFF_BYTES: u64 = 4096;
+
+/// Detects whether JSON data is an array of objects or JSON Lines.
+///
+/// Looks at the first non-whitespace character within the first `JSON_SNIFF_BYTES` bytes:
+/// `[` means an array, anything else is read as JSON Lines. The reader is rewound afterwards.
+fn detect_json_format(reader: &mut Box<dyn MmapBytesReader>) -> Result<JsonFormat, AppError> {
+    let mut head = Vec::new();
+    reader
+        .by_ref()
+        .take(JSON_SNIFF_BYTES)
+        .read_to_end(&mut head)?;
+    reader.seek(SeekFrom::Start(0))?;
+    // Skip a UTF-8 byte order mark before looking for the first character
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&head);
+    let first = head.iter().find(|b| !b.is_ascii_whitespace());
+    Ok(match first {
+        Some(b'[') => JsonFormat::Json,
+        _ => JsonFormat::JsonLines,
+    })
+}
+
+/// Loads a delimited text file (CSV, TSV, ...) with every column read as strings.
+///
+/// Quoted fields and escaped quotes are handled by the Polars CSV reader. The header row
+/// is read as data so duplicate names can be renamed before the DataFrame is built.
+/// Fields are trimmed and empty fields become nulls; rows with missing fields are
+/// padded with nulls and extra fields are ignored.
+///
+/// # Errors
+///
+/// Returns `AppError::DuplicateColumn` if `strict` is set and a header is repeated.
+fn load_csv_dataframe(path: &Path, separator: u8, strict: bool) -> Result<DataFrame, AppError> {
+    let raw = CsvReadOptions::default()
+        .with_has_header(false)
+        .with_infer_schema_length(Some(0))
+        .with_parse_options(
+            CsvParseOptions::default()
+                .with_separator(separator)
+                .with_truncate_ragged_lines(true),
+        )
+        .into_reader_with_file_handle(open_tabular_reader(path)?)
+        .finish()?;
+    if raw.height() == 0 {
+        return Err(AppError::Polars(PolarsError::NoData(
+            "CSV file is empty".into(),
+        )));
+    }
+
+    // Take the column names from the first row
+    let mut headers = raw
+        .get_columns()
+        .iter()
+        .map(|c| match c.get(0) {
+            Ok(AnyValue::String(s)) => s.trim().to_string(),
+            _ => String::new(),
+        })
+        .collect::<Vec<_>>();
+    rename_duplicate_headers(&mut headers, strict)?;
+
+    let mut columns = Vec::with_capacity(headers.len());
+    for (column, name) in raw.get_columns().iter().zip(&headers) {
+        let values: StringChunked = column
+            .str()?
+            .into_iter()
+            .skip(1)
+            .map(|v| v.map(str::trim).filter(|t| !t.is_empty()))
+            .collect();
+        columns.push(values.with_name(name.as_str().into()).into_series().into());
+    }
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Renames duplicate column headers so that a DataFrame can be built from them.
+///
+/// Every occurrence of a duplicated name gets a numeric suffix in order of appearance
+/// (e.g., two `value` columns become `value_1` and `value_2`), skipping suffixes that
+/// would clash with another header. A warning listing the renamed columns is printed.
+///
+/// # Errors
+///
+/// With `strict` (`--strict`), returns `AppError::DuplicateColumn` for the first
+/// duplicated name instead of renaming.
+fn rename_duplicate_headers(headers: &mut [String], strict: bool) -> Result<(), AppError> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in headers.iter() {
+        *counts.entry(name.clone()).or_default() += 1;
+    }
+    if counts.values().all(|&n| n == 1) {
+        return Ok(());
+    }
+    if strict {
+        if let Some(name) = headers.iter().find(|name| counts[name.as_str()] > 1) {
+            return Err(AppError::DuplicateColumn(name.clone()));
+        }
+    }
+
+    let mut taken: HashSet<String> = headers.iter().cloned().collect();
+    let mut next_suffix: HashMap<String, usize> = HashMap::new();
+    let mut renamed = Vec::new();
+    for name in headers.iter_mut() {
+        if counts[name.as_str()] < 2 {
+            continue;
+        }
+        let suffix = next_suffix.entry(name.clone()).or_insert(1);
+        let mut new_name = format!("{}_{}", name, suffix);
+        while taken.contains(&new_name) {
+            *suffix += 1;
+            new_name = format!("{}_{}", name, suffix);
+        }
+        *suffix += 1;
+        taken.insert(new_name.clone());
+        renamed.push(format!("'{}' -> '{}'", name, new_name));
+        *name = new_name;
+    }
+    status!(
+        "  -> Warning: Renamed duplicate columns: {}",
+        renamed.join(", ")
+    );
+    Ok(())
+}
+
+/// Parses a column type name of `--column-types` into a Polars `DataType`.
+///
+/// Datetimes use millisecond precision without a time zone, like automatically
+/// detected datetime columns.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidColumnType` if the name is not a supported type.
+pub fn parse_dtype(s: &str) -> Result<DataType, AppError> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "string" => Ok(DataType::String),
+        "float64" => Ok(DataType::Float64),
+        "int64" => Ok(DataType::Int64),
+        "datetime" => Ok(DataType::Datetime(TimeUnit::Milliseconds, None)),
+        "date" => Ok(DataType::Date),
+        "bool" => Ok(DataType::Boolean),
+        _ => Err(AppError::InvalidColumnType(s.trim().to_string())),
+    }
+}
+
+/// Returns true if the type of a column is set with `--column-types`.
+fn has_explicit_type(cli: &Cli, name: &str) -> bool {
+    cli.column_types.iter().any(|(column, _)| column == name)
+}
+
+/// Casts the columns listed in `--column-types` to their given types.
+///
+/// Datetimes that the Polars cast does not understand
+/// are parsed with the same formats as automatic datetime detection, and `bool` accepts
+/// `true`/`false` and `1`/`0` in any case. Values that cannot be converted become
+/// missing, with a warning.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFound` for a missing column if `--strict` is set;
+/// otherwise a warning is printed.
+fn apply_column_types(df: &mut DataFrame, cli: &Cli) -> Result<(), AppError> {
+    for (name, dtype) in &cli.column_types {
+        let Ok(column) = df.column(name) else {
+            if cli.strict {
+                return Err(AppError::ColumnNotFound(name.clone()));
+            }
+            status!(
+                "  -> Warning: Column '{}' in --column-types was not found.",
+                name
+            );
+            continue;
+        };
+        let s = column.as_materialized_series().clone();
+
+        let cast = match (s.dtype(), dtype) {
+            (DataType::String, DataType::Boolean) => s
+                .str()?
+                .into_iter()
+                .map(|v| match v.map(str::to_ascii_lowercase).as_deref() {
+                    Some("true" | "1") => Some(true),
+                    Some("false" | "0") => Some(false),
+                    _ => None,
+                })
+                .collect::<BooleanChunked>()
+                .with_name(name.as_str().into())
+                .into_series(),
+            (DataType::String, DataType::Datetime(..)) => {
+                let cast = s.cast(dtype)?;
+                match parse_string_series_to_datetime(&s) {
+                    Some(parsed) if parsed.null_count() < cast.null_count() => parsed,
+                    _ => cast,
+                }
+            }
+            _ => s.cast(dtype)?,
+        };
+
+        let failed = cast.null_count().saturating_sub(s.null_count());
+        if failed > 0 {
+            status!(
+                "  -> Warning: {} value(s) of column '{}' could not be converted to {} and are treated as missing.",
+                failed,
+                name,
+                dtype
+            );
+        }
+        df.replace(name, cast)?;
+    }
+    Ok(())
+}
+
 /// Attempts to cast string columns to `Datetime` if they match common date/time formats.
 ///
 /// This function iterates through string columns and applies two parsing strategies:
@@ -133,7 +422,8 @@ pub fn load_dataframe(path: &Path, cli: &Cli) -> Result<DataFrame, AppError> {
 ///
 /// A column is only converted if at least 90% of its non-null values can be successfully parsed,
 /// preventing accidental conversion of columns with only a few date-like strings.
-fn try_cast_string_columns_to_datetime(df: &mut DataFrame) -> Result<(), AppError> {
+/// Columns listed in `--column-types` are left unchanged.
+fn try_cast_string_columns_to_datetime(df: &mut DataFrame, cli: &Cli) -> Result<(), AppError> {
     let col_names: Vec<String> = df
         .get_columns()
         .iter()
@@ -141,6 +431,9 @@ fn try_cast_string_columns_to_datetime(df: &mut DataFrame) -> Result<(), AppErro
         .collect();
 
     for name in col_names {
+        if has_explicit_type(cli, &name) {
+            continue;
+        }
         let s = df.column(&name)?.as_series().unwrap().clone();
         if matches!(s.dtype(), DataType::String) {
             let mut accepted = false;
@@ -266,7 +559,7 @@ fn try_parse_many(s: &str, fmts: &[String]) -> Option<i64> {
 /// A column is converted only if *all* of its non-null string values can be successfully
 /// parsed as a float. This strict rule helps avoid incorrectly converting mixed-type columns.
 /// It also specifically skips columns containing the special marker character, which is reserved
-/// for creating vertical marker lines in the plot.
+/// for creating vertical marker lines in the plot, and columns listed in `--column-types`.
 fn try_cast_string_columns_to_numeric(df: &mut DataFrame, cli: &Cli) -> Result<(), AppError> {
     let col_names: Vec<String> = df
         .get_columns()
@@ -275,6 +568,9 @@ fn try_cast_string_columns_to_numeric(df: &mut DataFrame, cli: &Cli) -> Result<(
         .collect();
 
     for name in col_names {
+        if has_explicit_type(cli, &name) {
+            continue;
+        }
         let s = df.column(&name)?.as_series().unwrap().clone();
         if matches!(s.dtype(), DataType::String) {
             // Check if the column contains the special marker.
@@ -321,8 +617,8 @@ fn try_cast_string_columns_to_numeric(df: &mut DataFrame, cli: &Cli) -> Result<(
             }
 
             if cli.debug {
-                println!("  -> Checking column '{}' for numeric conversion:", name);
-                println!(
+                status!("  -> Checking column '{}' for numeric conversion:", name);
+                status!(
                     "     Length: {}, Non-null count: {}, Has marker: {}, Has numeric: {}",
                     s.len(),
                     s.len() - s.null_count(),
@@ -331,9 +627,9 @@ fn try_cast_string_columns_to_numeric(df: &mut DataFrame, cli: &Cli) -> Result<(
                 );
 
                 // Add some debug output about the first few values
-                println!("     First few values:");
+                status!("     First few values:");
                 for (i, av) in s.iter().take(5).enumerate() {
-                    println!("     [{}]: {:?}", i, av);
+                    status!("     [{}]: {:?}", i, av);
                 }
             }
 
@@ -359,20 +655,49 @@ fn try_cast_string_columns_to_numeric(df: &mut DataFrame, cli: &Cli) -> Result<(
     Ok(())
 }
 
-/// Loads the first worksheet of an Excel file (`.xlsx`, `.xls`) into a DataFrame.
+/// Returns the worksheet names of an Excel file, in workbook order (`--list-sheets`).
+pub fn excel_sheet_names(path: &Path) -> Result<Vec<String>, AppError> {
+    Ok(open_workbook_auto(path)?.sheet_names())
+}
+
+/// Loads a worksheet of an Excel file (`.xlsx`, `.xls`) into a DataFrame.
 ///
-/// Uses the `calamine` crate to read the Excel data. It auto-detects the header row
+/// Uses the `calamine` crate to read the Excel data. The worksheet is chosen by `sheet`,
+/// defaulting to the first one. It auto-detects the header row
 /// by skipping initial empty rows. All data is initially read as strings and then
 /// passed through the same type inference pipeline as other file formats.
-fn load_excel_dataframe(path: &Path) -> Result<DataFrame, AppError> {
+///
+/// # Errors
+///
+/// Returns `AppError::SheetNotFound`, listing the available sheets, if the selected
+/// sheet does not exist, or `AppError::DuplicateColumn` if `strict` is set and a
+/// header is repeated.
+fn load_excel_dataframe(
+    path: &Path,
+    sheet: Option<&SheetSelector>,
+    strict: bool,
+) -> Result<DataFrame, AppError> {
     let mut workbook = open_workbook_auto(path)?;
-    let sheet_name = workbook
-        .sheet_names()
-        .first()
-        .cloned()
-        .ok_or_else(|| AppError::UnsupportedFormat(path.to_string_lossy().to_string()))?;
+    let sheet_names = workbook.sheet_names();
+    let sheet_name = match sheet {
+        None => sheet_names.first(),
+        Some(SheetSelector::Name(name)) => sheet_names.iter().find(|s| *s == name),
+        Some(SheetSelector::Index(index)) => sheet_names.get(*index),
+    };
+    let Some(sheet_name) = sheet_name.cloned() else {
+        let selected = match sheet {
+            Some(SheetSelector::Name(name)) => format!("'{}'", name),
+            Some(SheetSelector::Index(index)) => format!("#{}", index),
+            None => {
+                return Err(AppError::UnsupportedFormat(
+                    path.to_string_lossy().to_string(),
+                ))
+            }
+        };
+        return Err(AppError::SheetNotFound(selected, sheet_names));
+    };
 
-    let range = workbook.worksheet_range(&sheet_name).unwrap();
+    let range = workbook.worksheet_range(&sheet_name)?;
 
     // Collect all rows to find the header index and maximum column count.
     let rows: Vec<Vec<Data>> = range.rows().map(|r| r.to_vec()).collect();
@@ -410,6 +735,7 @@ fn load_excel_dataframe(path: &Path) -> Result<DataFrame, AppError> {
         };
         headers.push(final_name);
     }
+    rename_duplicate_headers(&mut headers, strict)?;
 
     // Initialize column vectors to store data as strings.
     let mut columns: Vec<Vec<Option<String>>> = vec![Vec::new(); col_count];
@@ -438,21 +764,105 @@ fn load_excel_dataframe(path: &Path) -> Result<DataFrame, AppError> {
     Ok(df)
 }
 
+/// Metadata of the default track of an audio file, as reported by its container.
+#[derive(Debug, Clone)]
+pub struct AudioMetadata {
+    /// Sample rate in Hz.
+    pub sample_rate: Option<u32>,
+    /// Number of bits per sample, for PCM-based codecs.
+    pub bits_per_sample: Option<u32>,
+    /// Number of audio channels.
+    pub channels: Option<usize>,
+    /// Short name of the codec (e.g., `pcm_s16le`, `mp3`).
+    pub codec: String,
+    /// Total duration of the track in seconds.
+    pub duration_seconds: Option<f64>,
+}
+
+impl AudioMetadata {
+    /// Prints the metadata as an indented summary (`--show-sample-rate` and `--debug`).
+    pub fn print(&self) {
+        let fmt_opt = |v: Option<u32>, unit: &str| {
+            v.map_or("unknown".to_string(), |v| format!("{} {}", v, unit))
+        };
+        status!("  -> Audio info:");
+        status!("     Sample rate: {}", fmt_opt(self.sample_rate, "Hz"));
+        status!("     Bit depth: {}", fmt_opt(self.bits_per_sample, "bits"));
+        status!(
+            "     Channels: {}",
+            self.channels
+                .map_or("unknown".to_string(), |c| c.to_string())
+        );
+        status!("     Codec: {}", self.codec);
+        match self.duration_seconds {
+            Some(duration) => status!("     Duration: {:.3} s", duration),
+            None => status!("     Duration: unknown"),
+        }
+    }
+}
+
+/// Reads the metadata of the default track of an audio file.
+///
+/// Only the container is probed; no audio is decoded.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened, its format is not recognized,
+/// or it has no default track.
+pub fn probe_audio_metadata(path: &Path) -> Result<AudioMetadata, AppError> {
+    let src = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(src), Default::default());
+    let probed = symphonia::default::get_probe().format(
+        &symphonia::core::probe::Hint::new(),
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let params = &probed
+        .format
+        .default_track()
+        .ok_or_else(|| {
+            AppError::Symphonia(symphonia::core::errors::Error::Unsupported(
+                "No default track found",
+            ))
+        })?
+        .codec_params;
+
+    let codec = symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .map_or("unknown", |d| d.short_name)
+        .to_string();
+    let duration_seconds = match (params.n_frames, params.sample_rate) {
+        (Some(frames), Some(rate)) if rate > 0 => Some(frames as f64 / rate as f64),
+        _ => None,
+    };
+    Ok(AudioMetadata {
+        sample_rate: params.sample_rate,
+        bits_per_sample: params.bits_per_sample,
+        channels: params.channels.map(|c| c.count()),
+        codec,
+        duration_seconds,
+    })
+}
+
 /// Loads an audio file and decodes its default track into a DataFrame.
 ///
 /// Uses the `symphonia` crate to handle various audio codecs and formats.
 /// The resulting DataFrame will contain a `sample_index` column and one column for
-/// each audio channel (e.g., `channel_0`, `channel_1`).
+/// each audio channel (e.g., `channel_0`, `channel_1`), or only for the channel
+/// selected with `--audio-channel`.
 ///
 /// # Arguments
 ///
 /// * `path` - A reference to the path of the file to load.
+/// * `cli` - The parsed command-line arguments (`--audio-channel`, `--clip-audio`, and
+///   the corrupt packet handling options).
 ///
 /// # Returns
 ///
 /// A `Result` containing a `DataFrame` with separate columns for each audio
 /// channel on success, or an `AppError` on failure.
-fn load_audio_dataframe(path: &Path) -> Result<DataFrame, AppError> {
+fn load_audio_dataframe(path: &Path, cli: &Cli) -> Result<DataFrame, AppError> {
     // Setup: Open file and initialize symphonia probe.
     let src = File::open(path)?;
     let mss = MediaSourceStream::new(Box::new(src), Default::default());
@@ -480,11 +890,51 @@ fn load_audio_dataframe(path: &Path) -> Result<DataFrame, AppError> {
         })?
         .count();
 
+    if let Some(channel) = cli.audio_channel {
+        if channel >= num_channels {
+            return Err(AppError::InvalidChannel(channel, num_channels));
+        }
+    }
+
     let dec_opts: DecoderOptions = Default::default();
     let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &dec_opts)?;
 
+    let track_id = track.id;
+    let time_base = track.codec_params.time_base.or_else(|| {
+        track
+            .codec_params
+            .sample_rate
+            .map(|rate| TimeBase::new(1, rate))
+    });
+
+    // Seek to the start of the requested clip, if any. The clip range is kept as track
+    // timestamps, which count sample frames for audio tracks.
+    let clip_range = match cli.clip_audio {
+        Some((start, end)) => {
+            let time_base = time_base.ok_or(AppError::Symphonia(
+                symphonia::core::errors::Error::Unsupported(
+                    "Cannot clip audio without a known time base or sample rate.",
+                ),
+            ))?;
+            let seeked = format.seek(
+                SeekMode::Accurate,
+                SeekTo::Time {
+                    time: Time::from(start),
+                    track_id: Some(track_id),
+                },
+            )?;
+            decoder.reset();
+            Some((
+                seeked.required_ts,
+                time_base.calc_timestamp(Time::from(end)),
+            ))
+        }
+        None => None,
+    };
+
     // Create a vector of vectors, one for each channel.
     let mut channels_data: Vec<Vec<f32>> = vec![Vec::new(); num_channels];
+    let mut skipped_packets = 0;
 
     // Decoding loop
     loop {
@@ -498,14 +948,47 @@ fn load_audio_dataframe(path: &Path) -> Result<DataFrame, AppError> {
             Err(err) => return Err(AppError::from(err)),
         };
 
-        // Decode the packet into an audio buffer.
-        let decoded = decoder.decode(&packet)?;
+        // Stop once the packet starts after the end of the clip.
+        if clip_range.is_some_and(|(_, end_ts)| packet.ts() >= end_ts) {
+            break;
+        }
+
+        // Decode the packet into an audio buffer, skipping corrupt packets if requested.
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(symphonia::core::errors::Error::DecodeError(_)) if cli.audio_skip_errors => {
+                skipped_packets += 1;
+                status!(
+                    "  -> Warning: skipped corrupt packet at offset {}",
+                    packet.ts()
+                );
+                if skipped_packets > cli.audio_max_errors {
+                    return Err(AppError::TooManyAudioErrors(cli.audio_max_errors));
+                }
+                continue;
+            }
+            Err(err) => return Err(AppError::from(err)),
+        };
+
+        // Keep only the frames inside the clip, since packets may straddle its boundaries.
+        let frames = decoded.frames() as u64;
+        let (first_frame, end_frame) = match clip_range {
+            Some((start_ts, end_ts)) => (
+                start_ts.saturating_sub(packet.ts()).min(frames),
+                end_ts.saturating_sub(packet.ts()).min(frames),
+            ),
+            None => (0, frames),
+        };
+        if end_frame <= first_frame {
+            continue;
+        }
 
         let mut sample_buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
         sample_buf.copy_interleaved_ref(decoded);
 
         // Get samples from the interleaved buffer
-        let samples = sample_buf.samples();
+        let samples = &sample_buf.samples()
+            [first_frame as usize * num_channels..end_frame as usize * num_channels];
 
         // Process interleaved samples
         for (c, channel_data) in channels_data.iter_mut().enumerate() {
@@ -519,16 +1002,28 @@ fn load_audio_dataframe(path: &Path) -> Result<DataFrame, AppError> {
         }
     }
 
+    if skipped_packets > 0 {
+        status!("  -> Skipped {} corrupt audio packet(s)", skipped_packets);
+    }
+
     // --- Create DataFrame from the separated channel data ---
 
+    // Keep only the requested channel, if any, tagged with its original index.
+    let channels: Vec<(usize, Vec<f32>)> = match cli.audio_channel {
+        Some(channel) => vec![(channel, channels_data.swap_remove(channel))],
+        None => channels_data.into_iter().enumerate().collect(),
+    };
+
     // Determine the number of samples from the first channel.
-    let num_samples = channels_data.first().map_or(0, |v| v.len());
+    let num_samples = channels.first().map_or(0, |(_, v)| v.len());
+
     if num_samples == 0 {
         return Ok(DataFrame::default()); // Return an empty DataFrame if no samples.
     }
 
-    // Create the 'sample_index' series.
-    let indices: Vec<u32> = (0..num_samples as u32).collect();
+    // Create the 'sample_index' series, counting from the start of the file when clipped.
+    let first_index = clip_range.map_or(0, |(start_ts, _)| start_ts as u32);
+    let indices: Vec<u32> = (first_index..first_index + num_samples as u32).collect();
     let mut column_vec = Vec::with_capacity(num_channels + 1);
 
     let sample_index_name: PlSmallStr = "sample_index".into();
@@ -540,9 +1035,8 @@ fn load_audio_dataframe(path: &Path) -> Result<DataFrame, AppError> {
     );
 
     // Create a Series for each channel's data.
-    for (i, channel_samples) in channels_data.iter().enumerate() {
+    for (i, mut samples) in channels {
         // Ensure all channels have the same length. Pad with zeros if necessary.
-        let mut samples = channel_samples.clone();
         samples.resize(num_samples, 0.0);
 
         let name: PlSmallStr = format!("channel_{}", i).try_into().unwrap();
@@ -558,3 +1052,37 @@ fn load_audio_dataframe(path: &Path) -> Result<DataFrame, AppError> {
     let df = DataFrame::new(column_vec)?;
     Ok(df)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn ipc_round_trip_keeps_dtypes() {
+        // Numeric- and date-like strings would be re-cast if the coercion passes ran.
+        let mut df = df!(
+            "id" => ["001", "002", "003"],
+            "day" => ["2024-01-01", "2024-01-02", "2024-01-03"],
+            "count" => [1i32, 2, 3],
+            "ratio" => [0.5f32, 1.5, 2.5],
+            "flag" => [true, false, true]
+        )
+        .unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "scatters_ipc_round_trip_{}.arrow",
+            std::process::id()
+        ));
+        IpcWriter::new(File::create(&path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+
+        let cli = Cli::parse_from(["scatters", path.to_str().unwrap()]);
+        let loaded = load_dataframe(&path, &cli);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.schema(), df.schema());
+        assert!(loaded.equals(&df));
+    }
+}
@@ -16,6 +16,14 @@ pub enum AppError {
     #[error("Invalid input path: {0} does not exist or is not a file/directory")]
     InvalidInputPath(PathBuf),
 
+    /// Error for when processing one of the input files fails.
+    #[error("Error processing file {path}: {source}", path = .0.display(), source = .1)]
+    FileFailed(PathBuf, Box<AppError>),
+
+    /// Error indicating that a single output file was given for several input files.
+    #[error("Output file {0} cannot be used for multiple input files; pass a directory instead")]
+    AmbiguousOutputPath(PathBuf),
+
     /// Error for when a file has an extension that is not supported by any of the data loaders.
     #[error("Unsupported file format for: {0}")]
     UnsupportedFormat(String),
@@ -40,6 +48,10 @@ pub enum AppError {
     #[error("Excel parsing error (Calamine): {0}")]
     Calamine(#[from] calamine::Error),
 
+    /// An error creating the thread pool used to process files in parallel.
+    #[error("Failed to start the worker threads: {0}")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
     /// An error from the Askama template engine during HTML rendering.
     #[error("HTML template rendering error: {0}")]
     Template(#[from] askama::Error),
@@ -48,6 +60,54 @@ pub enum AppError {
     #[error("Column '{0}' not found in the data")]
     ColumnNotFound(String),
 
+    /// Error for when a user-specified column name is not found, but a similarly named column exists.
+    #[error("Column '{0}' not found in the data; did you mean '{1}'?")]
+    ColumnNotFoundSimilar(String, String),
+
+    /// Error for when several user-specified column names are not found in the DataFrame.
+    #[error("{}", .0.join("; "))]
+    ColumnsNotFound(Vec<String>),
+
+    /// Error for when a file has several columns with the same name and `--strict` is set.
+    #[error("Duplicate column name '{0}' in the data")]
+    DuplicateColumn(String),
+
+    /// Error for when a `--column-types` type name is not recognized.
+    #[error("Unknown column type '{0}'; expected string, float64, int64, datetime, date, or bool")]
+    InvalidColumnType(String),
+
+    /// Error for when an `--expression-column` expression cannot be parsed.
+    #[error("Invalid expression '{0}': {1}")]
+    InvalidExpression(String, String),
+
+    /// Error for when a `--filter` condition cannot be parsed or evaluated.
+    #[error("Invalid filter '{0}': {1}")]
+    InvalidFilter(String, String),
+
+    /// Error for when the requested audio channel does not exist in the file.
+    #[error("Audio channel {0} not found; the file has {1} channel(s)")]
+    InvalidChannel(usize, usize),
+
+    /// Error for when `--resample` would create an unreasonably large number of buckets.
+    #[error("Resampling would create {0} buckets (maximum {1}); use a longer interval")]
+    InvalidResample(usize, usize),
+
+    /// Error for when more corrupt audio packets than allowed by `--audio-max-errors` were skipped.
+    #[error("More than {0} corrupt audio packets; the file may be badly damaged")]
+    TooManyAudioErrors(usize),
+
+    /// Error for when the `--sheet` selected worksheet does not exist in an Excel file.
+    #[error("Sheet {0} not found; available sheets: {sheets}", sheets = .1.join(", "))]
+    SheetNotFound(String, Vec<String>),
+
+    /// Error for when a config file is not valid TOML or sets an unknown option.
+    #[error("Invalid config file {path}: {message}", path = .0.display(), message = .1)]
+    InvalidConfig(PathBuf, String),
+
+    /// Error for when `--offline` is used with a build that does not embed ECharts.
+    #[error("--offline requires a build with the `embed-echarts` feature")]
+    OfflineUnavailable,
+
     /// Error indicating that no plottable (numeric) columns were found after selecting the X-axis.
     #[error("No numeric columns found to plot")]
     NoNumericColumns,
@@ -0,0 +1,238 @@
+//! Summary statistics for inspecting a file's columns without plotting it (`--print-stats`).
+//!
+//! The statistics are printed to stdout either as markdown tables (numeric and categorical
+//! columns are summarized separately) or, with `--json`, as a single JSON object that maps
+//! each input file to an array with one object per column.
+
+use crate::error::AppError;
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Maximum number of values listed in the frequency table of a categorical column.
+const TOP_VALUES: usize = 5;
+
+/// Per-column summary statistics.
+///
+/// Count and null count are reported for every column; the remaining statistics depend
+/// on the column type.
+#[derive(Serialize)]
+pub struct ColumnStats {
+    pub name: String,
+    pub dtype: String,
+    pub count: usize,
+    pub null_count: usize,
+    #[serde(flatten)]
+    pub summary: ColumnSummary,
+}
+
+/// Type-specific statistics of a column, tagged with a `kind` field in JSON output.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ColumnSummary {
+    /// Distribution statistics of a numeric column.
+    Numeric(NumericStats),
+    /// Value frequencies of a string column.
+    Categorical(CategoricalStats),
+    /// Columns of any other type (e.g., dates) only report counts.
+    Other,
+}
+
+/// Distribution statistics of a numeric column. All fields are `None` for an all-null column.
+#[derive(Serialize)]
+pub struct NumericStats {
+    pub mean: Option<f64>,
+    pub std: Option<f64>,
+    pub min: Option<f64>,
+    pub q25: Option<f64>,
+    pub median: Option<f64>,
+    pub q75: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Value frequencies of a categorical (string) column.
+#[derive(Serialize)]
+pub struct CategoricalStats {
+    /// Number of distinct non-null values.
+    pub unique: usize,
+    /// The most frequent value, if the column has any non-null values.
+    pub mode: Option<String>,
+    /// The most frequent values and their counts, in descending order of frequency.
+    pub top_values: Vec<(String, usize)>,
+}
+
+/// Computes summary statistics for every column of a DataFrame.
+///
+/// # Errors
+///
+/// Returns an error if a numeric column cannot be cast to `Float64` or the
+/// value counts of a string column cannot be computed.
+pub fn compute_column_stats(df: &DataFrame) -> Result<Vec<ColumnStats>, AppError> {
+    let mut stats = Vec::with_capacity(df.width());
+    for column in df.get_columns() {
+        let series = column.as_materialized_series();
+        let null_count = series.null_count();
+        let summary = match series.dtype() {
+            dtype if dtype.is_numeric() => ColumnSummary::Numeric(numeric_summary(series)?),
+            DataType::String => ColumnSummary::Categorical(categorical_summary(series)?),
+            _ => ColumnSummary::Other,
+        };
+        stats.push(ColumnStats {
+            name: series.name().to_string(),
+            dtype: series.dtype().to_string(),
+            count: series.len() - null_count,
+            null_count,
+            summary,
+        });
+    }
+    Ok(stats)
+}
+
+/// Computes the distribution statistics of a numeric series.
+///
+/// Quantiles use linear interpolation and the standard deviation is the sample
+/// standard deviation (`ddof = 1`). NaN values are treated like any other float.
+fn numeric_summary(s: &Series) -> Result<NumericStats, AppError> {
+    let values = s.cast(&DataType::Float64)?;
+    let values = values.f64()?;
+    Ok(NumericStats {
+        mean: values.mean(),
+        std: values.std(1),
+        min: values.min(),
+        q25: values.quantile(0.25, QuantileMethod::Linear)?,
+        median: values.quantile(0.5, QuantileMethod::Linear)?,
+        q75: values.quantile(0.75, QuantileMethod::Linear)?,
+        max: values.max(),
+    })
+}
+
+/// Computes the unique count, mode, and top value frequencies of a categorical series.
+///
+/// Null values are ignored. Uses Polars `value_counts`, so the order of values with
+/// equal frequencies is unspecified.
+pub fn categorical_summary(s: &Series) -> Result<CategoricalStats, AppError> {
+    let counts_name = if s.name().as_str() == "count" {
+        "counts"
+    } else {
+        "count"
+    };
+    let counts = s
+        .drop_nulls()
+        .value_counts(true, false, counts_name.into(), false)?;
+    let values = counts.column(s.name().as_str())?.cast(&DataType::String)?;
+    let frequencies = counts.column(counts_name)?.cast(&DataType::UInt64)?;
+
+    let top_values: Vec<(String, usize)> = values
+        .str()?
+        .into_iter()
+        .zip(frequencies.u64()?)
+        .take(TOP_VALUES)
+        .filter_map(|(value, n)| Some((value?.to_string(), n? as usize)))
+        .collect();
+    Ok(CategoricalStats {
+        unique: counts.height(),
+        mode: top_values.first().map(|(value, _)| value.clone()),
+        top_values,
+    })
+}
+
+/// Prints summary statistics for every column of a DataFrame to stdout as markdown tables.
+///
+/// # Errors
+///
+/// Returns an error if the statistics cannot be computed.
+pub fn print_summary_stats(df: &DataFrame) -> Result<(), AppError> {
+    let stats = compute_column_stats(df)?;
+    status!("{}", markdown_table(&stats));
+    Ok(())
+}
+
+/// Prints the summary statistics of several files to stdout as one JSON object, keyed by
+/// input path (`--print-stats --json`).
+///
+/// This is the only output on stdout in this mode, so it can be piped to tools like `jq`.
+///
+/// # Errors
+///
+/// Returns an error if the statistics cannot be serialized.
+pub fn print_summary_stats_json(
+    stats: &BTreeMap<String, Vec<ColumnStats>>,
+) -> Result<(), AppError> {
+    println!("{}", serde_json::to_string_pretty(stats)?);
+    Ok(())
+}
+
+/// Formats summary statistics as markdown, with separate tables for numeric (and other)
+/// columns and for categorical columns.
+fn markdown_table(stats: &[ColumnStats]) -> String {
+    let mut numeric = vec![
+        "| column | dtype | count | null_count | mean | std | min | 25% | 50% | 75% | max |"
+            .to_string(),
+        "|---|---|---:|---:|---:|---:|---:|---:|---:|---:|---:|".to_string(),
+    ];
+    let mut categorical = vec![
+        "| column | dtype | count | null_count | unique | mode | top values |".to_string(),
+        "|---|---|---:|---:|---:|---|---|".to_string(),
+    ];
+    for s in stats {
+        let prefix = format!(
+            "| {} | {} | {} | {} |",
+            s.name, s.dtype, s.count, s.null_count
+        );
+        match &s.summary {
+            ColumnSummary::Categorical(c) => {
+                let top_values = c
+                    .top_values
+                    .iter()
+                    .map(|(value, n)| format!("{} ({})", value, n))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                categorical.push(format!(
+                    "{} {} | {} | {} |",
+                    prefix,
+                    c.unique,
+                    c.mode.as_deref().unwrap_or("-"),
+                    top_values
+                ));
+            }
+            summary => {
+                let values = match summary {
+                    ColumnSummary::Numeric(n) => {
+                        [n.mean, n.std, n.min, n.q25, n.median, n.q75, n.max]
+                    }
+                    _ => [None; 7],
+                };
+                let numbers = values
+                    .iter()
+                    .map(|v| format_stat(*v))
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                numeric.push(format!("{} {} |", prefix, numbers));
+            }
+        }
+    }
+
+    let mut sections = Vec::new();
+    if numeric.len() > 2 {
+        sections.push(format!("### Numeric columns\n\n{}", numeric.join("\n")));
+    }
+    if categorical.len() > 2 {
+        sections.push(format!(
+            "### Categorical columns\n\n{}",
+            categorical.join("\n")
+        ));
+    }
+    sections.join("\n\n")
+}
+
+/// Formats a statistic with up to 4 decimal places, or `-` if it is missing.
+fn format_stat(value: Option<f64>) -> String {
+    match value {
+        Some(v) if v.fract() == 0.0 => format!("{}", v),
+        Some(v) => {
+            let formatted = format!("{:.4}", v);
+            formatted.trim_end_matches('0').to_string()
+        }
+        None => "-".to_string(),
+    }
+}
@@ -7,30 +7,70 @@
 //!
 //! The library is structured into several modules:
 //! - `cli`: Defines the command-line interface.
+//! - `config`: Reads default option values from a TOML config file.
 //! - `data_loader`: Handles reading various file formats into DataFrames.
 //! - `processing`: Logic for selecting X and Y axes and preparing data for plotting.
 //! - `plotter`: Generates the final HTML/JavaScript plot from the prepared data.
+//! - `inspect`: Computes per-column summary statistics (`--print-stats`).
+//! - `update`: Merges new data into a previously generated plot (`--append`).
+//! - `progress`: Shows a progress bar while processing several files.
+//! - `summary`: Writes machine-readable JSON summaries of the plots (`--json-summary`).
 //! - `error`: Defines the application's custom error type.
 
+use polars::prelude::DataFrame;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use walkdir::WalkDir;
 
+/// Prints a status line to stdout like `println!`, keeping it clear of the progress bar.
+///
+/// Status lines go to stderr instead when stdout carries machine-readable output
+/// (see `STATUS_TO_STDERR`).
+macro_rules! status {
+    ($($arg:tt)*) => {
+        $crate::progress::suspend(|| {
+            if $crate::STATUS_TO_STDERR.load(::std::sync::atomic::Ordering::Relaxed) {
+                eprintln!($($arg)*)
+            } else {
+                println!($($arg)*)
+            }
+        })
+    };
+}
+
 pub mod cli;
+pub mod config;
 pub mod data_loader;
 pub mod error;
+pub mod inspect;
 pub mod plotter;
 pub mod processing;
+pub mod progress;
+pub mod summary;
+pub mod update;
 
 use crate::cli::Cli;
 use crate::error::AppError;
+use crate::plotter::PlotSummary;
+
+/// Number of bytes in a megabyte, as used for reporting output sizes.
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// Set while stdout carries machine-readable output (`--print-stats --json`), so that
+/// `status!` lines go to stderr and do not corrupt it.
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
 
 /// The main entry point for the application logic.
 ///
 /// This function orchestrates the entire process:
 /// 1.  It finds all supported files based on the input path (which can be a file or directory).
-/// 2.  It iterates through each file, calling `process_single_file` to handle the plotting.
-/// 3.  It prints progress and completion messages to the console.
+/// 2.  It processes the files in parallel (see `--jobs`), calling `process_single_file`
+///     for each one, or plots all files together with `process_combined` if `--combine` is set.
+/// 3.  It writes an index page linking to all generated plots, if requested.
+/// 4.  It prints progress and completion messages to the console.
 ///
 /// # Arguments
 ///
@@ -38,26 +78,111 @@ use crate::error::AppError;
 ///
 /// # Errors
 ///
-/// Returns an error if file discovery or processing fails for any of the files.
+/// Returns an error if file discovery fails, or if processing any of the files fails
+/// without `--continue-on-error`.
 pub fn run(cli: &Cli) -> Result<(), AppError> {
+    if cli.offline && plotter::EMBEDDED_ECHARTS.is_none() {
+        return Err(AppError::OfflineUnavailable);
+    }
+    STATUS_TO_STDERR.store(cli.print_stats && cli.json, Ordering::Relaxed);
+
     // 1. Discover files to process
     let files_to_process = find_supported_files(&cli.input_path)?;
     if files_to_process.is_empty() {
-        println!("No supported files found in the specified path.");
+        status!("No supported files found in the specified path.");
         return Ok(());
     }
 
-    println!("Found {} files to process...", files_to_process.len());
+    status!("Found {} files to process...", files_to_process.len());
 
-    // 2. Process each file
-    for file_path in files_to_process {
-        println!("Processing '{}'...", file_path.display());
-        if let Err(e) = process_single_file(&file_path, cli) {
-            eprintln!("  -> Error processing file {}: {}", file_path.display(), e);
+    if let Some(output_file) = explicit_output_file(cli) {
+        if files_to_process.len() > 1 && !cli.combine {
+            return Err(AppError::AmbiguousOutputPath(output_file.to_path_buf()));
+        }
+    }
+
+    // Statistics as JSON are collected from all files into a single document
+    if cli.print_stats && cli.json {
+        return print_stats_json(&files_to_process, cli);
+    }
+
+    // 2. Process each file, keeping track of the generated plots
+    let mut plots: Vec<PlotSummary> = Vec::new();
+    if cli.combine {
+        match process_combined(&files_to_process, cli) {
+            Ok(saved) => plots.extend(saved),
+            Err(e) if cli.continue_on_error => {
+                eprintln!("  -> Error saving the combined plot: {}", e)
+            }
+            Err(e) => return Err(e),
+        }
+    } else {
+        // Files are independent, so they are processed in parallel; collecting the
+        // results keeps the plots in input order for the index page.
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(cli.jobs.map_or(0, usize::from))
+            .build()?;
+        let bar = (files_to_process.len() > 1).then(|| progress::start(files_to_process.len()));
+        // Without --continue-on-error, files not yet started are skipped after a failure.
+        let failed = AtomicBool::new(false);
+        let process = |file_path: &PathBuf| {
+            if failed.load(Ordering::Relaxed) && !cli.continue_on_error {
+                return Ok(Vec::new());
+            }
+            status!("Processing '{}'...", file_path.display());
+            if let Some(bar) = &bar {
+                bar.set_message(file_path.display().to_string());
+            }
+            let result = process_single_file(file_path, cli);
+            if result.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+            if let Some(bar) = &bar {
+                bar.inc(1);
+            }
+            result
+        };
+        // Statistics and sheet names are printed while processing, so files are then
+        // processed one at a time to keep each file's output under its "Processing" line.
+        let results: Vec<_> = if cli.print_stats || cli.list_sheets {
+            files_to_process.iter().map(process).collect()
+        } else {
+            pool.install(|| files_to_process.par_iter().map(process).collect())
+        };
+        progress::finish();
+        let mut failures = 0;
+        for (file_path, result) in files_to_process.iter().zip(results) {
+            match result {
+                Ok(saved) => plots.extend(saved),
+                Err(e) if cli.continue_on_error => {
+                    eprintln!("  -> Error processing file {}: {}", file_path.display(), e);
+                    failures += 1;
+                }
+                Err(e) => return Err(AppError::FileFailed(file_path.clone(), Box::new(e))),
+            }
         }
+        if failures > 0 {
+            eprintln!("{} files failed", failures);
+        }
+    }
+
+    // 3. Link all generated plots from an index page, if requested
+    if cli.index_page && !plots.is_empty() {
+        let index_dir = index_directory(cli);
+        let index_path = index_dir.join("index.html");
+        fs::create_dir_all(&index_dir)?;
+        fs::write(
+            &index_path,
+            plotter::generate_index_html(&plots, &index_dir)?,
+        )?;
+        status!("Index page saved to '{}'", index_path.display());
     }
 
-    println!("Done.");
+    let total_output_bytes: u64 = plots.iter().map(|p| p.bytes).sum();
+    if total_output_bytes > 0 {
+        status!("Total output size: {}", format_size(total_output_bytes));
+    }
+    status!("Done.");
     Ok(())
 }
 
@@ -71,39 +196,321 @@ pub fn run(cli: &Cli) -> Result<(), AppError> {
 /// # Errors
 ///
 /// Returns an error if any step (loading, processing, plotting, or saving) fails.
-fn process_single_file(file_path: &Path, cli: &Cli) -> Result<(), AppError> {
+///
+/// # Returns
+///
+/// A summary of each HTML file written for this input (one per chunk when chunking).
+fn process_single_file(file_path: &Path, cli: &Cli) -> Result<Vec<PlotSummary>, AppError> {
+    if cli.list_sheets {
+        print_sheet_names(file_path)?;
+        return Ok(Vec::new());
+    }
+
     // 1. Load data into a DataFrame
-    let df = data_loader::load_dataframe(file_path, cli)?;
+    let df = load_prepared_dataframe(file_path, cli)?;
+
+    if cli.print_stats {
+        inspect::print_summary_stats(&df)?;
+        return Ok(Vec::new());
+    }
+
+    let output_path = generate_output_path(file_path, cli);
+
+    // Split long data into one plot per chunk of rows, if requested
+    if let Some(chunk_size) = cli.chunk_size {
+        let chunks = processing::split_dataframe_into_chunks(df, chunk_size, cli.chunk_overlap);
+        let chunk_count = chunks.len();
+        status!(
+            "  -> Splitting into {} chunks of up to {} rows",
+            chunk_count,
+            chunk_size
+        );
+        let mut saved = Vec::with_capacity(chunk_count);
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let (rows, columns) = (chunk.height(), chunk.width());
+            let mut plot_data = processing::prepare_plot_data(chunk, cli, file_path)?;
+            plot_data.title = format!("{} (chunk {}/{})", plot_data.title, i + 1, chunk_count);
+            let path = chunk_output_path(&output_path, i);
+            let bytes = save_plot(&plot_data, &path, cli)?;
+            if cli.json_summary {
+                let summary_path = summary::write_json_summary(&plot_data, file_path, &path)?;
+                status!("  -> Summary saved to '{}'", summary_path.display());
+            }
+            saved.push(PlotSummary {
+                path,
+                rows,
+                columns,
+                bytes,
+            });
+        }
+        return Ok(saved);
+    }
+
+    // 2. Prepare data for plotting (select X and Y series)
+    let (rows, columns) = (df.height(), df.width());
+    let mut plot_data = processing::prepare_plot_data(df, cli, file_path)?;
+
+    // 3. Merge with the existing plot, if appending
+    if cli.append {
+        match update::parse_existing_html(&output_path) {
+            Some(existing) => {
+                status!(
+                    "  -> Appending new data to existing plot '{}'",
+                    output_path.display()
+                );
+                update::merge_existing_series(&mut plot_data, existing)?;
+            }
+            None => status!("  -> No existing plot data found; creating a new plot."),
+        }
+    }
+
+    // 4. Generate and save the HTML plot
+    let bytes = save_plot(&plot_data, &output_path, cli)?;
+    if cli.json_summary {
+        let summary_path = summary::write_json_summary(&plot_data, file_path, &output_path)?;
+        status!("  -> Summary saved to '{}'", summary_path.display());
+    }
+    Ok(vec![PlotSummary {
+        path: output_path,
+        rows,
+        columns,
+        bytes,
+    }])
+}
+
+/// Prints the worksheet names of an Excel file with their `--sheet` indices (`--list-sheets`).
+fn print_sheet_names(file_path: &Path) -> Result<(), AppError> {
+    let is_excel = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx") || ext.eq_ignore_ascii_case("xls"));
+    if !is_excel {
+        status!("  -> Not an Excel file; no sheets to list.");
+        return Ok(());
+    }
+    for (i, name) in data_loader::excel_sheet_names(file_path)?
+        .iter()
+        .enumerate()
+    {
+        status!("  {}: {}", i, name);
+    }
+    Ok(())
+}
+
+/// Loads a file and applies the DataFrame-level transformations (`--fft`,
+/// `--audio-time-axis`, `--filter`, `--head`, `--tail`, `--pivot`, `--convert-tz`), printing the detected columns in debug mode.
+///
+/// # Errors
+///
+/// Returns an error if loading or transforming the data fails.
+fn load_prepared_dataframe(file_path: &Path, cli: &Cli) -> Result<DataFrame, AppError> {
+    let mut df = data_loader::load_dataframe(file_path, cli)?;
+
+    // Summarize audio files, and apply the options that need their sample rate
+    if data_loader::is_audio_file(file_path) {
+        let metadata = data_loader::probe_audio_metadata(file_path)?;
+        if cli.show_sample_rate || cli.debug {
+            metadata.print();
+        }
+        if (cli.fft || cli.audio_time_axis) && df.column("sample_index").is_ok() {
+            let sample_rate = metadata.sample_rate.ok_or(AppError::Symphonia(
+                symphonia::core::errors::Error::Unsupported(
+                    "The sample rate of the audio track is not known.",
+                ),
+            ))?;
+            if cli.fft {
+                df = processing::compute_fft_dataframe(&df, sample_rate)?;
+            } else {
+                processing::sample_index_to_seconds(&mut df, sample_rate)?;
+            }
+        }
+    }
+
+    // Keep only the rows matching the filter condition, if requested
+    if let Some(condition) = &cli.filter {
+        let loaded_rows = df.height();
+        df = processing::filter_rows(df, condition)?;
+        if cli.debug {
+            status!("  -> Filter kept {} of {} rows", df.height(), loaded_rows);
+        }
+    }
+
+    // Keep only the first or last rows for a preview, if requested
+    let loaded_rows = df.height();
+    if let Some(n) = cli.head {
+        df = df.head(Some(n));
+    } else if let Some(n) = cli.tail {
+        df = df.tail(Some(n));
+    }
+    if cli.debug && df.height() < loaded_rows {
+        status!(
+            "  -> Kept {} of {} rows ({} dropped)",
+            df.height(),
+            loaded_rows,
+            loaded_rows - df.height()
+        );
+    }
+
+    // Pivot long-format data to one column per category, if requested
+    if let (true, Some(index), Some(column), Some(value)) = (
+        cli.pivot,
+        &cli.pivot_index,
+        &cli.pivot_column,
+        &cli.pivot_value,
+    ) {
+        df = processing::pivot_long_to_wide(&df, index, column, value)?;
+    }
+
+    // Convert datetimes to the target time zone, if requested
+    if let Some((from, to)) = &cli.convert_tz {
+        processing::convert_time_zones(&mut df, from, to)?;
+    }
 
     if cli.debug {
-        println!("  -> Detected columns:");
+        status!("  -> Detected columns:");
         for s in df.get_columns() {
-            println!("     - {}: {:?}", s.name(), s.dtype());
+            status!("     - {}: {:?}", s.name(), s.dtype());
         }
-        println!("  -> Shape: {} rows x {} cols", df.height(), df.width());
+        status!("  -> Shape: {} rows x {} cols", df.height(), df.width());
     }
+    Ok(df)
+}
 
-    // 2. Prepare data for plotting (select X and Y series)
-    let plot_data = processing::prepare_plot_data(df, cli, file_path)?;
+/// Prints the statistics of every file as one JSON object keyed by input path
+/// (`--print-stats --json`).
+///
+/// With `--continue-on-error`, files that fail to load are reported on stderr and
+/// left out.
+///
+/// # Errors
+///
+/// Returns `AppError::FileFailed` for the first file that fails without
+/// `--continue-on-error`, or an error if the statistics cannot be serialized.
+fn print_stats_json(files: &[PathBuf], cli: &Cli) -> Result<(), AppError> {
+    let mut stats = BTreeMap::new();
+    let mut failures = 0;
+    for file_path in files {
+        status!("Processing '{}'...", file_path.display());
+        let file_stats = load_prepared_dataframe(file_path, cli)
+            .and_then(|df| inspect::compute_column_stats(&df));
+        match file_stats {
+            Ok(file_stats) => {
+                stats.insert(file_path.display().to_string(), file_stats);
+            }
+            Err(e) if cli.continue_on_error => {
+                eprintln!("  -> Error processing file {}: {}", file_path.display(), e);
+                failures += 1;
+            }
+            Err(e) => return Err(AppError::FileFailed(file_path.clone(), Box::new(e))),
+        }
+    }
+    if failures > 0 {
+        eprintln!("{} files failed", failures);
+    }
+    inspect::print_summary_stats_json(&stats)?;
+    status!("Done.");
+    Ok(())
+}
+
+/// Loads every file and plots the series of all of them in one HTML file (`--combine`).
+///
+/// With `--continue-on-error`, files that fail to load or process are reported and
+/// left out of the plot.
+///
+/// # Errors
+///
+/// Returns `AppError::FileFailed` for the first file that fails without
+/// `--continue-on-error`, or an error if rendering or saving the combined plot fails.
+fn process_combined(files: &[PathBuf], cli: &Cli) -> Result<Vec<PlotSummary>, AppError> {
+    let mut plots = Vec::with_capacity(files.len());
+    let (mut rows, mut columns) = (0, 0);
+    let mut failures = 0;
+    for file_path in files {
+        status!("Processing '{}'...", file_path.display());
+        let plot_data = load_prepared_dataframe(file_path, cli).and_then(|df| {
+            rows += df.height();
+            columns += df.width();
+            processing::prepare_plot_data(df, cli, file_path)
+        });
+        match plot_data {
+            Ok(plot_data) => plots.push((data_loader::data_file_stem(file_path), plot_data)),
+            Err(e) if cli.continue_on_error => {
+                eprintln!("  -> Error processing file {}: {}", file_path.display(), e);
+                failures += 1;
+            }
+            Err(e) => return Err(AppError::FileFailed(file_path.clone(), Box::new(e))),
+        }
+    }
+    if failures > 0 {
+        eprintln!("{} files failed", failures);
+    }
 
-    // 3. Generate the HTML plot
-    let html_content = plotter::generate_html_plot(&plot_data)?;
+    let Some(plot_data) = processing::merge_plot_data(plots) else {
+        return Ok(Vec::new());
+    };
+    let path = match explicit_output_file(cli) {
+        Some(output_file) => output_file.to_path_buf(),
+        None => index_directory(cli).join("combined.html"),
+    };
+    let bytes = save_plot(&plot_data, &path, cli)?;
+    Ok(vec![PlotSummary {
+        path,
+        rows,
+        columns,
+        bytes,
+    }])
+}
+
+/// Generates the HTML plot for the prepared data and writes it to `output_path`,
+/// creating parent directories as needed.
+///
+/// The file size is printed in debug mode, and a warning is printed if it exceeds
+/// `--warn-size-mb`, since browsers struggle to open very large plots.
+///
+/// # Errors
+///
+/// Returns an error if rendering the plot or writing the file fails.
+///
+/// # Returns
+///
+/// The size of the written file in bytes.
+fn save_plot(
+    plot_data: &processing::PlotData,
+    output_path: &Path,
+    cli: &Cli,
+) -> Result<u64, AppError> {
+    let html_content = plotter::generate_html_plot(plot_data)?;
 
-    // 4. Save the output
-    let output_path = generate_output_path(file_path, cli);
     fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
-    fs::write(&output_path, html_content)?;
+    fs::write(output_path, html_content)?;
 
-    println!("  -> Plot saved to '{}'", output_path.display());
+    status!("  -> Plot saved to '{}'", output_path.display());
 
-    Ok(())
+    let size = fs::metadata(output_path)?.len();
+    if cli.debug {
+        status!("  -> Output size: {}", format_size(size));
+    }
+    if size as f64 > cli.warn_size_mb * BYTES_PER_MB {
+        status!(
+            "  -> Warning: The plot is {}, which browsers may struggle to open. Consider lowering --downsample-threshold or splitting the data with --chunk-size.",
+            format_size(size)
+        );
+    }
+
+    Ok(size)
+}
+
+/// Formats a size in bytes as megabytes with one decimal place, e.g. `15.3 MB`.
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / BYTES_PER_MB)
 }
 
 /// Finds all supported files based on a given path.
 ///
+/// The path `-` (stdin) is returned as-is.
 /// If the path is a file, it checks if its extension is supported.
 /// If the path is a directory, it recursively walks the directory and collects all
-/// files with supported extensions.
+/// files with supported extensions, including compressed ones such as `data.csv.gz`.
 ///
 /// # Arguments
 ///
@@ -113,27 +520,19 @@ fn process_single_file(file_path: &Path, cli: &Cli) -> Result<(), AppError> {
 ///
 /// A `Result` containing a vector of `PathBuf`s for all supported files found,
 /// or an `AppError::InvalidInputPath` if the path doesn't exist.
-fn find_supported_files(path: &Path) -> Result<Vec<std::path::PathBuf>, AppError> {
+fn find_supported_files(path: &Path) -> Result<Vec<PathBuf>, AppError> {
     let mut files = Vec::new();
-    let supported_extensions: Vec<&str> = vec![
-        "csv", "parquet", "json", "jsonl", "ndjson", "xlsx", "xls", "wav", "mp3", "flac", "ogg",
-        "m4a", "aac",
-    ];
-
-    if path.is_file() {
-        if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-            if supported_extensions.contains(&ext.to_lowercase().as_str()) {
-                files.push(path.to_path_buf());
-            }
+
+    if data_loader::is_stdin(path) {
+        files.push(path.to_path_buf());
+    } else if path.is_file() {
+        if data_loader::is_supported_file(path) {
+            files.push(path.to_path_buf());
         }
     } else if path.is_dir() {
         for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_type().is_file() {
-                if let Some(ext) = entry.path().extension().and_then(|s| s.to_str()) {
-                    if supported_extensions.contains(&ext.to_lowercase().as_str()) {
-                        files.push(entry.path().to_path_buf());
-                    }
-                }
+            if entry.file_type().is_file() && data_loader::is_supported_file(entry.path()) {
+                files.push(entry.path().to_path_buf());
             }
         }
     } else {
@@ -142,11 +541,52 @@ fn find_supported_files(path: &Path) -> Result<Vec<std::path::PathBuf>, AppError
     Ok(files)
 }
 
+/// Returns the positional output path if it names an `.html` file rather than a directory.
+fn explicit_output_file(cli: &Cli) -> Option<&Path> {
+    cli.output_path.as_deref().filter(|p| {
+        p.extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("html"))
+    })
+}
+
+/// Determines the directory of the `--index-page` index.
+///
+/// This is the output directory (or the parent of an explicit output file) if one was
+/// given, and otherwise the input folder, or the parent of a single input file.
+fn index_directory(cli: &Cli) -> PathBuf {
+    if let Some(output_file) = explicit_output_file(cli) {
+        output_file.parent().unwrap_or(Path::new(".")).to_path_buf()
+    } else if let Some(output_dir) = cli.output_path.as_ref().or(cli.output.as_ref()) {
+        output_dir.clone()
+    } else if cli.input_path.is_dir() {
+        cli.input_path.clone()
+    } else {
+        cli.input_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .to_path_buf()
+    }
+}
+
+/// Derives the output path of the `index`-th chunk from a plot's output path,
+/// e.g. `data.html` becomes `data_chunk_0000.html`.
+fn chunk_output_path(output_path: &Path, index: usize) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    output_path.with_file_name(format!("{}_chunk_{:04}.html", stem, index))
+}
+
 /// Determines the output path for a generated HTML plot.
 ///
-/// If an output directory is specified via CLI arguments, the plot is saved inside that
-/// directory with the name `<input_stem>.html`.
+/// If the positional output path names an `.html` file, it is used as-is.
+/// If an output directory is specified (either as the positional output path or via
+/// `--output-dir`), the plot is saved inside that directory with the name `<input_stem>.html`,
+/// where compression extensions are also dropped (`data.csv.gz` gives `data.html`).
 /// Otherwise, it is saved next to the input file with the same name.
+/// Data read from stdin is saved as `output.html`, in the current directory by default.
 ///
 /// # Arguments
 ///
@@ -156,11 +596,17 @@ fn find_supported_files(path: &Path) -> Result<Vec<std::path::PathBuf>, AppError
 /// # Returns
 ///
 /// A `PathBuf` representing the full path for the output HTML file.
-fn generate_output_path(input_path: &Path, cli: &Cli) -> std::path::PathBuf {
-    let stem = input_path.file_stem().unwrap_or_default().to_string_lossy();
+fn generate_output_path(input_path: &Path, cli: &Cli) -> PathBuf {
+    let stem = if data_loader::is_stdin(input_path) {
+        "output".to_string()
+    } else {
+        data_loader::data_file_stem(input_path)
+    };
     let default_output_name = format!("{}.html", stem);
 
-    if let Some(output_dir) = &cli.output {
+    if let Some(output_file) = explicit_output_file(cli) {
+        output_file.to_path_buf()
+    } else if let Some(output_dir) = cli.output_path.as_ref().or(cli.output.as_ref()) {
         output_dir.join(default_output_name)
     } else {
         // Default to saving next to the input file
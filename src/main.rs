@@ -1,20 +1,40 @@
 //! The binary entry point for the `scatters` command-line application.
 //!
 //! This file is responsible for:
-//! 1. Parsing command-line arguments using the `clap` crate.
-//! 2. Calling the main application logic in the `scatters` library.
+//! 1. Parsing command-line arguments using the `clap` crate, with defaults from a config file.
+//! 2. Calling the main application logic in the `scatters` library, or printing a shell
+//!    completion script with `--generate-completions`.
 //! 3. Handling and printing any errors that occur during execution.
 
-use clap::Parser;
-use scatters::cli::Cli;
+use clap_complete::Shell;
+use scatters::config;
 
 /// The main function of the executable.
 ///
-/// Parses command-line arguments and invokes the library's `run` function.
+/// Parses command-line arguments (see `config` for defaults from a config file) and
+/// invokes the library's `run` function.
 /// If an error occurs, it is printed to stderr and the process exits with a non-zero status code.
 fn main() {
-    // Parse command-line arguments
-    let cli = Cli::parse();
+    // Parse command-line arguments, merged with the config file (if any)
+    let cli = match config::parse_args_with_config(std::env::args_os().collect()) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Print a shell completion script instead of plotting, if requested
+    if let Some(shell) = &cli.generate_completions {
+        let shell: Shell = shell.parse().expect("clap only accepts supported shells");
+        clap_complete::generate(
+            shell,
+            &mut config::command(),
+            "scatters",
+            &mut std::io::stdout(),
+        );
+        return;
+    }
 
     // Run the main application logic from the library
     if let Err(e) = scatters::run(&cli) {
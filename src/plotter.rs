@@ -3,13 +3,41 @@
 //! It takes the processed `PlotData` and uses the `askama` template engine
 //! to render a self-contained HTML file. This file includes the necessary
 //! JavaScript to power an interactive ECharts scatter plot, with the data
-//! embedded directly as JSON.
+//! embedded directly as JSON. Alternative chart types (e.g., pie charts) are
+//! rendered through a simpler, shared chart template.
 
 use crate::error::AppError;
-use crate::processing::{any_value_to_f64, PlotData};
+use crate::processing::{
+    any_value_to_f64, BoxplotData, Heatmap2dData, PlotData, RadarData, MAX_Y_AXES,
+};
 use askama::Template;
 use polars::prelude::*;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The Okabe-Ito palette, which stays distinguishable under common color vision deficiencies.
+const OKABE_ITO_PALETTE: [&str; 8] = [
+    "#E69F00", "#56B4E9", "#009E73", "#F0E442", "#0072B2", "#D55E00", "#CC79A7", "#000000",
+];
+
+/// Marker shapes cycled through with the colorblind palette, so series differ by shape as well as color.
+const COLORBLIND_SYMBOLS: [&str; 3] = ["circle", "rect", "triangle"];
+
+/// The number of cells per axis in the spatial hash used for density-weighted opacity.
+const DENSITY_GRID_SIZE: usize = 100;
+
+/// The lowest opacity assigned to points in the densest regions.
+const MIN_DENSITY_OPACITY: f64 = 0.05;
+
+/// The minified ECharts library, embedded with the `embed-echarts` feature for `--offline`.
+/// The checksum-verified bundle is provided by `build.rs`.
+#[cfg(feature = "embed-echarts")]
+pub const EMBEDDED_ECHARTS: Option<&str> =
+    Some(include_str!(concat!(env!("OUT_DIR"), "/echarts.min.js")));
+/// The minified ECharts library, embedded with the `embed-echarts` feature for `--offline`.
+#[cfg(not(feature = "embed-echarts"))]
+pub const EMBEDDED_ECHARTS: Option<&str> = None;
 
 /// An `askama` template for the HTML page.
 ///
@@ -19,16 +47,140 @@ use serde_json::Value;
 #[template(path = "page.jinja2")]
 struct PageTemplate<'a> {
     title: &'a str,
+    echarts_js: Option<&'a str>,
+    x_label: Option<&'a str>,
+    y_label: Option<&'a str>,
+    x_tick_count: Option<usize>,
+    y_tick_count: Option<usize>,
     autoscale_y: bool,
+    data_zoom: bool,
+    persist_zoom: bool,
+    show_legend: bool,
+    legend_selected_json: &'a str,
+    series_search: bool,
+    x_max_ms: Option<f64>,
+    sticky_tooltip: bool,
+    keyboard_shortcuts: bool,
+    opacity_by_density: bool,
     animations: bool,
     max_decimals: i32,
     use_white_theme: bool,
+    dark_mode_auto: bool,
+    use_utc: bool,
     downsampled: bool,
+    sampled_rows: Option<(usize, usize)>,
     x_axis_type: &'a str,
+    invert_x: bool,
+    invert_y: bool,
+    x_log_base: f64,
+    y_axis_type: &'a str,
+    y_log_base: f64,
     x_axis_label_extra: &'a str,
+    x_min: Option<f64>,
+    x_max: Option<f64>,
     y_min: f64,
     y_max: f64,
     series_json: &'a str,
+    palette_json: &'a str,
+    text_style_json: &'a str,
+    grid_left: &'a str,
+    grid_right: &'a str,
+    grid_top: &'a str,
+    grid_bottom: &'a str,
+    separate_y_axes: bool,
+    y_axes_json: &'a str,
+}
+
+/// An `askama` template for non-scatter charts (e.g., pie charts).
+///
+/// The chart-specific ECharts option is built ahead of time and embedded as JSON;
+/// the template adds the shared title, legend, theme, and toolbox settings.
+#[derive(Template)]
+#[template(path = "chart.jinja2")]
+struct ChartTemplate<'a> {
+    title: &'a str,
+    echarts_js: Option<&'a str>,
+    animations: bool,
+    max_decimals: i32,
+    use_white_theme: bool,
+    dark_mode_auto: bool,
+    show_legend: bool,
+    palette_json: &'a str,
+    text_style_json: &'a str,
+    option_json: &'a str,
+}
+
+/// An `askama` template for the index page listing the plots generated in one run.
+#[derive(Template)]
+#[template(path = "index.jinja2")]
+struct IndexTemplate<'a> {
+    title: &'a str,
+    entries: Vec<IndexEntry>,
+}
+
+/// A link on the index page.
+struct IndexEntry {
+    href: String,
+    name: String,
+    rows: usize,
+    columns: usize,
+}
+
+/// A generated plot, as listed on the index page.
+pub struct PlotSummary {
+    /// The path of the generated HTML file.
+    pub path: PathBuf,
+    /// The number of rows of the plotted data.
+    pub rows: usize,
+    /// The number of columns of the plotted data.
+    pub columns: usize,
+    /// The size of the HTML file in bytes.
+    pub bytes: u64,
+}
+
+/// Generates an HTML index page with one link per generated plot.
+///
+/// Links are relative to `index_dir` when a plot is saved inside it, and absolute otherwise.
+///
+/// # Arguments
+///
+/// * `plots` - The generated plots, in the order they are listed.
+/// * `index_dir` - The directory the index page is saved in.
+///
+/// # Returns
+///
+/// A `Result` containing the rendered HTML content as a `String`, or an `AppError` if templating fails.
+pub fn generate_index_html(plots: &[PlotSummary], index_dir: &Path) -> Result<String, AppError> {
+    let entries = plots
+        .iter()
+        .map(|plot| {
+            let (name, href) = match plot.path.strip_prefix(index_dir) {
+                Ok(relative) => (
+                    relative.to_string_lossy().to_string(),
+                    relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                ),
+                Err(_) => {
+                    let path = plot.path.to_string_lossy().to_string();
+                    (path.clone(), path)
+                }
+            };
+            IndexEntry {
+                href,
+                name,
+                rows: plot.rows,
+                columns: plot.columns,
+            }
+        })
+        .collect();
+    let template = IndexTemplate {
+        title: "Scatters plots",
+        entries,
+    };
+    Ok(template.render()?)
 }
 
 /// Generates a self-contained HTML file with an interactive ECharts plot.
@@ -41,8 +193,21 @@ struct PageTemplate<'a> {
 ///
 /// A `Result` containing the rendered HTML content as a `String`, or an `AppError` if templating fails.
 pub fn generate_html_plot(plot_data: &PlotData) -> Result<String, AppError> {
+    if let Some(slices) = &plot_data.pie_slices {
+        return generate_pie_html(plot_data, slices);
+    }
+    if let Some(radar) = &plot_data.radar {
+        return generate_radar_html(plot_data, radar);
+    }
+    if let Some(boxplot) = &plot_data.boxplot {
+        return generate_boxplot_html(plot_data, boxplot);
+    }
+    if let Some(heatmap) = &plot_data.heatmap_2d {
+        return generate_heatmap_2d_html(plot_data, heatmap);
+    }
+
     // Convert Polars Series into a format suitable for ECharts JSON.
-    let series_json_objects = build_series_json(plot_data)?;
+    let (series_json_objects, y_axes) = build_series_json(plot_data)?;
     let series_json_str = series_json_objects.join(",");
 
     // Determine ECharts x-axis type based on the data type of the X series from the first series pair.
@@ -52,19 +217,24 @@ pub fn generate_html_plot(plot_data: &PlotData) -> Result<String, AppError> {
         .map(|(_, x_series, _)| match x_series.dtype() {
             DataType::Datetime(_, _) | DataType::Date => "time",
             DataType::String => "category",
+            _ if plot_data.log_x => "log",
             _ => "value",
         })
         .unwrap_or("value");
 
     // Add a custom formatter for numeric X-axis labels.
-    let x_axis_label_extra = if x_axis_type == "value" {
+    let x_axis_label_extra = if x_axis_type == "value" || x_axis_type == "log" {
         ", formatter: formatNumber"
     } else {
         ""
     };
 
     // Compute initial Y-axis limits with padding by iterating through all points.
-    let (y_min, y_max) = {
+    // Stacked series are left to ECharts, since their totals exceed the individual values,
+    // and so are log axes, where a linearly padded range could go below zero.
+    let (y_min, y_max) = if plot_data.stack.is_some() || plot_data.log_y {
+        (f64::NAN, f64::NAN)
+    } else {
         let mut min_v = f64::INFINITY;
         let mut max_v = f64::NEG_INFINITY;
         for (_, _, ys) in &plot_data.series_list {
@@ -78,45 +248,365 @@ pub fn generate_html_plot(plot_data: &PlotData) -> Result<String, AppError> {
             }
         }
 
-        if min_v.is_finite() && max_v.is_finite() {
-            let span = (max_v - min_v).abs();
-            let pad = if span == 0.0 { 1.0 } else { span * 0.10 };
-            (min_v - pad, max_v + pad)
-        } else {
-            (f64::NAN, f64::NAN) // ECharts interprets NaN/null as 'auto'
-        }
+        padded_range(min_v, max_v)
     };
+    let y_min = plot_data.y_min.unwrap_or(y_min);
+    let y_max = plot_data.y_max.unwrap_or(y_max);
+
+    // In multi-Y-axis mode each series brings its own axis, and with --right-axis there
+    // is a left and a right axis; otherwise a single shared one.
+    let separate_y_axes = plot_data.multi_yaxis || !plot_data.right_axis_series.is_empty();
+    let y_axes_json = if separate_y_axes {
+        serde_json::to_string(&y_axes)?
+    } else {
+        serde_json::to_string(&[serde_json::json!({ "name": "", "min": y_min, "max": y_max })])?
+    };
+
+    let palette_json = palette_json(plot_data)?;
+    let text_style_json = text_style_json(plot_data)?;
+    let legend_selected_json = legend_selected_json(plot_data)?;
+
+    // Resolve the grid margins, falling back to defaults for any side not specified.
+    let [left, right, top, bottom] = &plot_data.grid_margins;
+    let grid_left = left
+        .clone()
+        .unwrap_or_else(|| estimate_left_margin(plot_data));
+    let grid_right = right.as_deref().unwrap_or("2%");
+    let grid_top = top.as_deref().unwrap_or("60");
+    let grid_bottom = bottom.as_deref().unwrap_or("6%");
 
     // Create the template context and render the HTML.
+    let echarts_js = embedded_echarts(plot_data);
     let template = PageTemplate {
         title: &plot_data.title,
+        echarts_js: echarts_js.as_deref(),
+        x_label: plot_data.x_label.as_deref(),
+        y_label: plot_data.y_label.as_deref(),
+        x_tick_count: plot_data.x_tick_count,
+        y_tick_count: plot_data.y_tick_count,
         autoscale_y: plot_data.autoscale_y,
+        data_zoom: plot_data.data_zoom,
+        persist_zoom: plot_data.persist_zoom,
+        show_legend: plot_data.show_legend,
+        legend_selected_json: &legend_selected_json,
+        series_search: plot_data.series_search,
+        x_max_ms: plot_data.x_max_ms,
+        sticky_tooltip: plot_data.sticky_tooltip,
+        keyboard_shortcuts: plot_data.keyboard_shortcuts,
+        opacity_by_density: plot_data.opacity_by_density,
         animations: plot_data.animations,
         max_decimals: plot_data.max_decimals,
         use_white_theme: plot_data.use_white_theme,
+        dark_mode_auto: plot_data.dark_mode_auto,
+        use_utc: plot_data.use_utc,
         downsampled: plot_data.downsampled,
+        sampled_rows: plot_data.sampled_rows,
         x_axis_type,
+        invert_x: plot_data.invert_x,
+        invert_y: plot_data.invert_y,
+        x_log_base: plot_data.x_log_base,
+        y_axis_type: if plot_data.log_y { "log" } else { "value" },
+        y_log_base: plot_data.y_log_base,
         x_axis_label_extra,
+        x_min: plot_data.x_min,
+        x_max: plot_data.x_max,
         y_min,
         y_max,
         series_json: &series_json_str,
+        palette_json: &palette_json,
+        text_style_json: &text_style_json,
+        grid_left: &grid_left,
+        grid_right,
+        grid_top,
+        grid_bottom,
+        separate_y_axes,
+        y_axes_json: &y_axes_json,
     };
 
     Ok(template.render()?)
 }
 
+/// Generates a self-contained HTML pie chart with one slice per column.
+///
+/// The X series is ignored; each slice is labeled with its column name.
+fn generate_pie_html(plot_data: &PlotData, slices: &[(String, f64)]) -> Result<String, AppError> {
+    let data: Vec<Value> = slices
+        .iter()
+        .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+        .collect();
+    let option = serde_json::json!({
+        "tooltip": { "trigger": "item" },
+        "series": [{
+            "type": "pie",
+            "radius": "60%",
+            "center": ["50%", "55%"],
+            "label": { "formatter": "{b}: {d}%" },
+            "data": data
+        }]
+    });
+    render_chart(plot_data, &option)
+}
+
+/// Generates a self-contained HTML radar chart with one spoke per column.
+///
+/// Each spoke's maximum is the largest value of its column among the drawn rows,
+/// so every column uses its own scale.
+fn generate_radar_html(plot_data: &PlotData, radar: &RadarData) -> Result<String, AppError> {
+    let indicators: Vec<Value> = radar
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(ci, name)| {
+            let max = radar
+                .rows
+                .iter()
+                .filter_map(|(_, values)| values[ci])
+                .filter(|v| v.is_finite())
+                .fold(f64::NEG_INFINITY, f64::max);
+            let max = if max.is_finite() && max > 0.0 {
+                max
+            } else {
+                1.0
+            };
+            serde_json::json!({ "name": name, "max": max })
+        })
+        .collect();
+    let data: Vec<Value> = radar
+        .rows
+        .iter()
+        .map(|(label, values)| serde_json::json!({ "name": label, "value": values }))
+        .collect();
+    let option = serde_json::json!({
+        "tooltip": { "trigger": "item" },
+        "radar": { "indicator": indicators, "center": ["50%", "55%"], "radius": "60%" },
+        "series": [{ "type": "radar", "data": data }]
+    });
+    render_chart(plot_data, &option)
+}
+
+/// Generates a self-contained HTML box-and-whisker plot with one box per column.
+fn generate_boxplot_html(plot_data: &PlotData, boxplot: &BoxplotData) -> Result<String, AppError> {
+    let names: Vec<&str> = boxplot
+        .boxes
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let data: Vec<&[f64; 5]> = boxplot.boxes.iter().map(|(_, stats)| stats).collect();
+    let option = serde_json::json!({
+        "tooltip": { "trigger": "item" },
+        "legend": { "show": false },
+        "grid": { "left": "2%", "right": "2%", "top": 60, "bottom": "6%", "containLabel": true },
+        "xAxis": { "type": "category", "data": names },
+        "yAxis": { "type": "value", "scale": true },
+        "series": [{ "name": "boxplot", "type": "boxplot", "data": data }]
+    });
+    render_chart(plot_data, &option)
+}
+
+/// Generates a self-contained HTML 2D density heatmap, coloring each cell by its point count.
+fn generate_heatmap_2d_html(
+    plot_data: &PlotData,
+    heatmap: &Heatmap2dData,
+) -> Result<String, AppError> {
+    let max_count = heatmap.cells.iter().map(|c| c[2]).max().unwrap_or(0).max(1);
+    let option = serde_json::json!({
+        "tooltip": { "position": "top" },
+        "legend": { "show": false },
+        "grid": { "left": "2%", "right": "2%", "top": 60, "bottom": 90, "containLabel": true },
+        "xAxis": { "type": "category", "name": heatmap.x_name, "nameLocation": "middle", "nameGap": 30, "data": heatmap.x_labels },
+        "yAxis": { "type": "category", "name": heatmap.y_name, "data": heatmap.y_labels },
+        "visualMap": {
+            "min": 0,
+            "max": max_count,
+            "calculable": true,
+            "orient": "horizontal",
+            "left": "center",
+            "bottom": 10
+        },
+        "series": [{ "name": "count", "type": "heatmap", "data": heatmap.cells }]
+    });
+    render_chart(plot_data, &option)
+}
+
+/// Renders a non-scatter chart from a prebuilt ECharts option using `ChartTemplate`.
+fn render_chart(plot_data: &PlotData, option: &Value) -> Result<String, AppError> {
+    let palette_json = palette_json(plot_data)?;
+    let text_style_json = text_style_json(plot_data)?;
+    let option_json = serde_json::to_string(option)?;
+    let echarts_js = embedded_echarts(plot_data);
+    let template = ChartTemplate {
+        title: &plot_data.title,
+        echarts_js: echarts_js.as_deref(),
+        animations: plot_data.animations,
+        max_decimals: plot_data.max_decimals,
+        use_white_theme: plot_data.use_white_theme,
+        dark_mode_auto: plot_data.dark_mode_auto,
+        show_legend: plot_data.show_legend,
+        palette_json: &palette_json,
+        text_style_json: &text_style_json,
+        option_json: &option_json,
+    };
+    Ok(template.render()?)
+}
+
+/// Returns the ECharts source to inline in the page with `--offline`, or `None` to load
+/// it from the CDN.
+///
+/// Closing `</script` tags inside the source are escaped so they cannot end the inline
+/// `<script>` element early.
+fn embedded_echarts(plot_data: &PlotData) -> Option<String> {
+    EMBEDDED_ECHARTS
+        .filter(|_| plot_data.offline)
+        .map(|js| js.replace("</script", "<\\/script"))
+}
+
+/// Resolves the color palette as a JSON array; an empty string keeps the theme's default colors.
+fn palette_json(plot_data: &PlotData) -> Result<String, AppError> {
+    Ok(match plot_data.palette.as_str() {
+        "colorblind" => serde_json::to_string(&OKABE_ITO_PALETTE)?,
+        _ => String::new(),
+    })
+}
+
+/// Returns the names by which options refer to a series: its own name and, for series
+/// renamed by `--diff` or `--cumsum`, the name of its source column.
+fn option_names<'a>(plot_data: &'a PlotData, name: &'a str) -> impl Iterator<Item = &'a str> {
+    std::iter::once(name).chain(plot_data.series_sources.get(name).map(String::as_str))
+}
+
+/// Builds the legend's initial `selected` map, hiding the series that match `--default-hidden`;
+/// an empty string if no series is hidden.
+fn legend_selected_json(plot_data: &PlotData) -> Result<String, AppError> {
+    let hidden: serde_json::Map<String, Value> = plot_data
+        .series_list
+        .iter()
+        .filter(|(name, _, _)| {
+            plot_data
+                .default_hidden
+                .iter()
+                .any(|pattern| option_names(plot_data, name).any(|name| glob_match(pattern, name)))
+        })
+        .map(|(name, _, y_series)| (series_label(plot_data, name, y_series), Value::Bool(false)))
+        .collect();
+    if hidden.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(serde_json::to_string(&hidden)?)
+}
+
+/// Returns the legend label of a series: its name, followed by its number of points
+/// with `--show-n-points`.
+///
+/// Series are looked up by their plain name everywhere else (line widths, shapes,
+/// `--default-hidden`), so only the rendered label carries the count.
+fn series_label(plot_data: &PlotData, name: &str, y_series: &Series) -> String {
+    if plot_data.show_n_points {
+        format!("{} ({} pts)", name, y_series.len())
+    } else {
+        name.to_string()
+    }
+}
+
+/// Matches a name against a glob pattern, where `*` matches any run of characters
+/// (including none) and `?` matches exactly one character.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` in the pattern and the name position it was tried at.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, tried)) => {
+                    p = star + 1;
+                    n = tried + 1;
+                    backtrack = Some((star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Builds the global text style from the font overrides; an empty string if there are none.
+fn text_style_json(plot_data: &PlotData) -> Result<String, AppError> {
+    let mut text_style = serde_json::Map::new();
+    if let Some(size) = plot_data.font_size {
+        text_style.insert("fontSize".to_string(), Value::from(size));
+    }
+    if let Some(family) = &plot_data.font_family {
+        text_style.insert("fontFamily".to_string(), Value::from(family.as_str()));
+    }
+    Ok(if text_style.is_empty() {
+        String::new()
+    } else {
+        serde_json::to_string(&text_style)?
+    })
+}
+
+/// Estimates a left grid margin from the length of the longest series name.
+///
+/// Assumes roughly 4 pixels of extra room per character (capped at 40 characters)
+/// on top of a 20px base, so long labels are not clipped at the chart edge.
+fn estimate_left_margin(plot_data: &PlotData) -> String {
+    let max_len = plot_data
+        .series_list
+        .iter()
+        .map(|(name, _, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    format!("{}px", 20 + 4 * max_len.min(40))
+}
+
 /// Builds the JavaScript object strings for each data series to be plotted.
 ///
 /// This function iterates through each Y-series, pairs its values with the corresponding
 /// X-series values, and serializes them into a JSON structure compatible with ECharts.
 /// It also handles a special case where the special marker in a string column creates a vertical
 /// `markLine` in the plot instead of a data point.
-fn build_series_json(plot_data: &PlotData) -> Result<Vec<String>, AppError> {
+///
+/// Points with zero or negative coordinates on a logarithmic axis are dropped with a warning.
+///
+/// Alongside the series objects, it returns one Y-axis descriptor (`name`, `min`, `max`)
+/// per axis used in multi-Y-axis mode, or for the left and right axes with `--right-axis`,
+/// with ranges padded like the shared Y-axis.
+fn build_series_json(plot_data: &PlotData) -> Result<(Vec<String>, Vec<Value>), AppError> {
     let mut series_objects = Vec::new();
+    let mut axis_ranges: Vec<(String, f64, f64)> = Vec::new();
+    // Both axes exist up front, since the first series may be on either of them.
+    // The right axis is named after its series, leaving out trend lines.
+    let right_axis = !plot_data.multi_yaxis && !plot_data.right_axis_series.is_empty();
+    if right_axis {
+        let right_name = plot_data
+            .right_axis_series
+            .iter()
+            .filter(|name| !plot_data.line_series.contains(*name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        for name in [plot_data.y_label.clone().unwrap_or_default(), right_name] {
+            axis_ranges.push((name, f64::INFINITY, f64::NEG_INFINITY));
+        }
+    }
 
-    for (y_name, x_series, y_series) in &plot_data.series_list {
+    for (i, (y_name, x_series, y_series)) in plot_data.series_list.iter().enumerate() {
+        // Only numeric X-axes are made logarithmic by `--log-x`.
+        let log_x = plot_data.log_x && x_series.dtype().is_numeric();
+        let mut non_positive = 0;
         // Zip X and Y series into [x, y] pairs, filtering out nulls.
-        let mut data_points: Vec<[Value; 2]> = Vec::new();
+        let mut data_points: Vec<Vec<Value>> = Vec::new();
+        // Numeric coordinates of each data point, used for density-weighted opacity.
+        let mut coords: Vec<Option<(f64, f64)>> = Vec::new();
         let mut mark_lines_data: Vec<Value> = Vec::new();
         let mut x_min = f64::INFINITY;
         let mut x_max = f64::NEG_INFINITY;
@@ -130,7 +620,12 @@ fn build_series_json(plot_data: &PlotData) -> Result<Vec<String>, AppError> {
                     if s == plot_data.special_marker {
                         mark_lines_data.push(serde_json::json!({
                             "xAxis": any_value_to_json_value(x_val.clone()),
-                            "lineStyle": { "color": "#c23531", "width": 2, "type": "solid" },
+                            "lineStyle": {
+                                "color": plot_data.marker_color,
+                                "width": plot_data.marker_width,
+                                "opacity": plot_data.marker_opacity,
+                                "type": "solid"
+                            },
                             "symbol": "none"
                         }));
                         continue; // Skip adding to data_points.
@@ -138,28 +633,83 @@ fn build_series_json(plot_data: &PlotData) -> Result<Vec<String>, AppError> {
                 }
 
                 if !matches!(y_val, AnyValue::Null) {
+                    let is_non_positive =
+                        |v: &AnyValue| any_value_to_f64(v).is_some_and(|v| v <= 0.0);
+                    if (log_x && is_non_positive(&x_val))
+                        || (plot_data.log_y && is_non_positive(&y_val))
+                    {
+                        non_positive += 1;
+                        continue;
+                    }
+
                     // JSON values for rendering.
                     let x_json = any_value_to_json_value(x_val.clone());
                     let y_json = any_value_to_json_value(y_val.clone());
-                    data_points.push([x_json, y_json]);
+                    data_points.push(vec![x_json, y_json]);
 
                     // Decouple range calculations to correctly handle categorical X-axis.
-                    if let Some(xn) = any_value_to_f64(&x_val) {
-                        if xn.is_finite() {
-                            x_min = x_min.min(xn);
-                            x_max = x_max.max(xn);
-                        }
+                    let xn = any_value_to_f64(&x_val).filter(|v| v.is_finite());
+                    let yn = any_value_to_f64(&y_val).filter(|v| v.is_finite());
+                    if let Some(xn) = xn {
+                        x_min = x_min.min(xn);
+                        x_max = x_max.max(xn);
                     }
-                    if let Some(yn) = any_value_to_f64(&y_val) {
-                        if yn.is_finite() {
-                            y_min = y_min.min(yn);
-                            y_max = y_max.max(yn);
-                        }
+                    if let Some(yn) = yn {
+                        y_min = y_min.min(yn);
+                        y_max = y_max.max(yn);
                     }
+                    coords.push(xn.zip(yn));
                 }
             }
         }
 
+        if non_positive > 0 {
+            status!(
+                "  -> Warning: Skipped {} point(s) of '{}' with zero or negative values on a logarithmic axis",
+                non_positive, y_name
+            );
+        }
+
+        // Append each point's density-weighted opacity as the third data dimension.
+        if plot_data.opacity_by_density {
+            let opacities = density_opacities(&coords, (x_min, x_max), (y_min, y_max));
+            for (point, opacity) in data_points.iter_mut().zip(opacities) {
+                point.push(Value::from(opacity));
+            }
+        }
+
+        // Attach the `--mark-x` reference lines to the first series only, so they are drawn once.
+        if i == 0 {
+            for x in &plot_data.manual_mark_x {
+                mark_lines_data.push(serde_json::json!({
+                    "xAxis": x,
+                    "lineStyle": {
+                        "color": plot_data.marker_color,
+                        "width": plot_data.marker_width,
+                        "opacity": plot_data.marker_opacity,
+                        "type": "solid"
+                    },
+                    "symbol": "none"
+                }));
+            }
+        }
+
+        // Attach the `--mark-y` reference lines to the first series, or to all if requested.
+        if i == 0 || plot_data.mark_y_all_series {
+            for y in &plot_data.manual_mark_y {
+                mark_lines_data.push(serde_json::json!({
+                    "yAxis": y,
+                    "lineStyle": {
+                        "color": plot_data.marker_color,
+                        "width": plot_data.marker_width,
+                        "opacity": plot_data.marker_opacity,
+                        "type": "solid"
+                    },
+                    "symbol": "none"
+                }));
+            }
+        }
+
         let n_points = data_points.len();
 
         // Dynamically adjust symbol size based on the number of points for better readability.
@@ -196,8 +746,8 @@ fn build_series_json(plot_data: &PlotData) -> Result<Vec<String>, AppError> {
         };
 
         // Construct the base JSON object for the series.
-        let series_obj = serde_json::json!({
-            "name": y_name,
+        let mut series_obj = serde_json::json!({
+            "name": series_label(plot_data, y_name, y_series),
             "type": "scatter",
             "metaN": n_points,
             "metaXMin": x_min_val,
@@ -209,10 +759,156 @@ fn build_series_json(plot_data: &PlotData) -> Result<Vec<String>, AppError> {
             "markLine": { "data": mark_lines_data, "symbol": "none" }
         });
 
+        // In line mode, connect the points and apply the (possibly per-series) line width.
+        if plot_data.line_mode {
+            let width = option_names(plot_data, y_name)
+                .find_map(|name| plot_data.series_line_widths.get(name))
+                .copied()
+                .unwrap_or(plot_data.line_width);
+            series_obj["type"] = Value::from("line");
+            series_obj["lineStyle"] = serde_json::json!({ "width": width });
+            if let Some(step) = &plot_data.step {
+                series_obj["step"] = Value::from(step.as_str());
+            }
+        }
+
+        if plot_data.bar_mode {
+            series_obj["type"] = Value::from("bar");
+        }
+
+        // Trend lines are drawn as plain lines whatever the chart type.
+        if plot_data.line_series.contains(y_name) {
+            series_obj["type"] = Value::from("line");
+            series_obj["showSymbol"] = Value::from(false);
+            series_obj["lineStyle"] = serde_json::json!({ "width": plot_data.line_width });
+        }
+
+        // Stack the series; filled areas make stacked lines read as components of a total.
+        if let Some(stack) = &plot_data.stack {
+            series_obj["stack"] = Value::from(stack.as_str());
+            if plot_data.line_mode {
+                series_obj["areaStyle"] = serde_json::json!({});
+            }
+        }
+
+        // Attach the highlighted regions to the first series only, so they are drawn once.
+        if i == 0 && !plot_data.mark_regions.is_empty() {
+            let areas: Vec<Value> = plot_data
+                .mark_regions
+                .iter()
+                .map(|r| {
+                    serde_json::json!([
+                        { "name": r.label, "xAxis": r.start, "itemStyle": { "color": r.color } },
+                        { "xAxis": r.end }
+                    ])
+                })
+                .collect();
+            series_obj["markArea"] = serde_json::json!({ "silent": true, "data": areas });
+        }
+
+        // Apply the category shape from `--shape-column`, keeping the parent series' color group.
+        if let Some(shape) = plot_data.series_shapes.get(y_name) {
+            series_obj["symbol"] = Value::from(shape.symbol.as_str());
+            series_obj["metaColorGroup"] = Value::from(shape.color_group);
+        }
+
+        // Alternate marker shapes for dual (color + shape) differentiation.
+        if plot_data.palette == "colorblind" && plot_data.series_shapes.is_empty() {
+            series_obj["symbol"] = Value::from(COLORBLIND_SYMBOLS[i % COLORBLIND_SYMBOLS.len()]);
+        }
+
+        // Bind the series to its own Y-axis, with any overflow sharing the last one,
+        // or to the left (0) or right (1) axis.
+        let axis_index = if plot_data.multi_yaxis {
+            Some(i.min(MAX_Y_AXES - 1))
+        } else if right_axis {
+            Some(usize::from(plot_data.right_axis_series.contains(y_name)))
+        } else {
+            None
+        };
+        if let Some(axis_index) = axis_index {
+            series_obj["yAxisIndex"] = Value::from(axis_index);
+            if let Some((_, axis_min, axis_max)) = axis_ranges.get_mut(axis_index) {
+                *axis_min = axis_min.min(y_min);
+                *axis_max = axis_max.max(y_max);
+            } else {
+                axis_ranges.push((y_name.clone(), y_min, y_max));
+            }
+        }
+
         let series_obj_str = serde_json::to_string(&series_obj)?;
         series_objects.push(series_obj_str);
     }
-    Ok(series_objects)
+
+    let y_axes = axis_ranges
+        .into_iter()
+        .map(|(name, min_v, max_v)| {
+            let (axis_min, axis_max) = if plot_data.log_y {
+                // Log axes pick their own range; a linearly padded one could go below zero.
+                (None, None)
+            } else {
+                let (axis_min, axis_max) = padded_range(min_v, max_v);
+                (Some(axis_min), Some(axis_max))
+            };
+            serde_json::json!({
+                "name": name,
+                "min": plot_data.y_min.or(axis_min),
+                "max": plot_data.y_max.or(axis_max)
+            })
+        })
+        .collect();
+    Ok((series_objects, y_axes))
+}
+
+/// Computes a per-point opacity inversely proportional to the local point density.
+///
+/// Points are hashed into a `DENSITY_GRID_SIZE` x `DENSITY_GRID_SIZE` grid spanning the
+/// given X and Y ranges; each point's opacity is `1 / n`, where `n` is the number of points
+/// sharing its cell, clamped to `MIN_DENSITY_OPACITY`. Points without numeric coordinates
+/// are fully opaque.
+fn density_opacities(
+    coords: &[Option<(f64, f64)>],
+    (x_min, x_max): (f64, f64),
+    (y_min, y_max): (f64, f64),
+) -> Vec<f64> {
+    let cell_of = |v: f64, min: f64, max: f64| -> usize {
+        let span = max - min;
+        if span > 0.0 {
+            (((v - min) / span) * (DENSITY_GRID_SIZE - 1) as f64) as usize
+        } else {
+            0
+        }
+    };
+    let cells: Vec<Option<(usize, usize)>> = coords
+        .iter()
+        .map(|c| c.map(|(x, y)| (cell_of(x, x_min, x_max), cell_of(y, y_min, y_max))))
+        .collect();
+
+    let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+    for cell in cells.iter().flatten() {
+        *counts.entry(*cell).or_insert(0) += 1;
+    }
+
+    cells
+        .iter()
+        .map(|cell| match cell {
+            Some(cell) => (1.0 / counts[cell] as f64).max(MIN_DENSITY_OPACITY),
+            None => 1.0,
+        })
+        .collect()
+}
+
+/// Pads a `[min, max]` range by 10% of its span (or by 1.0 for a zero span).
+///
+/// Returns `(NaN, NaN)` if either bound is not finite, which ECharts treats as 'auto'.
+fn padded_range(min_v: f64, max_v: f64) -> (f64, f64) {
+    if min_v.is_finite() && max_v.is_finite() {
+        let span = (max_v - min_v).abs();
+        let pad = if span == 0.0 { 1.0 } else { span * 0.10 };
+        (min_v - pad, max_v + pad)
+    } else {
+        (f64::NAN, f64::NAN)
+    }
 }
 
 /// Converts a Polars `AnyValue` to a `serde_json::Value`.
@@ -233,6 +929,8 @@ fn any_value_to_json_value(av: AnyValue) -> Value {
         AnyValue::Int16(v) => v.into(),
         AnyValue::Int32(v) => v.into(),
         AnyValue::Int64(v) => v.into(),
+        // JSON numbers beyond the i64 range are emitted as (lossy) floats.
+        AnyValue::Int128(v) => i64::try_from(v).map_or_else(|_| (v as f64).into(), Value::from),
         AnyValue::Float32(v) => v.into(),
         AnyValue::Float64(v) => v.into(),
         // Polars Date is days since epoch.
@@ -252,3 +950,24 @@ fn any_value_to_json_value(av: AnyValue) -> Value {
         _ => Value::String(av.to_string()), // Fallback for other types.
     }
 }
+
+#[cfg(all(test, feature = "embed-echarts"))]
+mod tests {
+    use super::*;
+
+    /// Upper bound on the size of the embedded bundle, which is added to every `--offline`
+    /// plot; a full (unminified) build or an unexpectedly large release fails the test.
+    const MAX_EMBEDDED_ECHARTS_BYTES: usize = 1_500_000;
+
+    #[test]
+    fn embedded_echarts_size_is_bounded() {
+        let js = EMBEDDED_ECHARTS.expect("the embed-echarts feature embeds ECharts");
+        assert!(!js.is_empty());
+        assert!(
+            js.len() <= MAX_EMBEDDED_ECHARTS_BYTES,
+            "embedded ECharts is {} bytes, more than {}",
+            js.len(),
+            MAX_EMBEDDED_ECHARTS_BYTES
+        );
+    }
+}
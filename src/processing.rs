@@ -5,115 +5,2201 @@
 //! It also resolves the plot title and other plot-specific configurations.
 
 use crate::cli::Cli;
+use crate::data_loader;
 use crate::error::AppError;
 use polars::prelude::*;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+/// Marker shapes assigned to the categories of `--shape-column`, in order of first appearance.
+const CATEGORY_SHAPES: [&str; 5] = ["circle", "rect", "triangle", "diamond", "arrow"];
+
+/// A series to plot, as a (name, x_series, y_series) tuple.
+type SeriesEntry = (String, Series, Series);
+
+/// Styling for a series produced by splitting on `--shape-column`.
+pub struct SeriesShape {
+    /// The ECharts symbol used for the series' points.
+    pub symbol: String,
+    /// The index of the original Y series, so all of its categories share one color.
+    pub color_group: usize,
+}
+
+/// `--rolling-mean` trend lines are only downsampled above this multiple of the
+/// downsampling threshold, so the smoothed line stays continuous.
+const TREND_DOWNSAMPLE_FACTOR: usize = 4;
+
+/// The `--sort-by` value that stands for the selected X-axis column.
+const SORT_BY_X: &str = "_x";
+
+/// The maximum number of Y-axes created in multi-Y-axis mode.
+pub const MAX_Y_AXES: usize = 6;
+
+/// Maximum number of time buckets a series may be resampled into.
+const MAX_RESAMPLE_BUCKETS: usize = 10_000_000;
+
+/// The fill color used for highlighted regions that do not specify one.
+const DEFAULT_REGION_COLOR: &str = "rgba(200,200,200,0.2)";
+
+/// A highlighted region of the X-axis, rendered as an ECharts `markArea`.
+pub struct MarkRegion {
+    /// The start of the region, in X-axis units (milliseconds for datetimes).
+    pub start: f64,
+    /// The end of the region, in X-axis units (milliseconds for datetimes).
+    pub end: f64,
+    /// The label drawn inside the region.
+    pub label: String,
+    /// The CSS fill color of the region.
+    pub color: String,
+}
+
+/// The data for a radar chart: one spoke per column and one polygon per row.
+pub struct RadarData {
+    /// The column names, one per spoke.
+    pub columns: Vec<String>,
+    /// The rows to draw, each as a (label, values) pair with one value per column.
+    pub rows: Vec<(String, Vec<Option<f64>>)>,
+}
+
+/// The five-number summary of each column for a box-and-whisker plot.
+pub struct BoxplotData {
+    /// One (column name, `[lower whisker, Q1, median, Q3, upper whisker]`) entry per box.
+    pub boxes: Vec<(String, [f64; 5])>,
+}
+
+/// A 2D histogram of (x, y) pairs for a density heatmap.
+pub struct Heatmap2dData {
+    /// The name of the column binned along the X-axis.
+    pub x_name: String,
+    /// The name of the column binned along the Y-axis.
+    pub y_name: String,
+    /// The center of each X bin, formatted for the axis labels.
+    pub x_labels: Vec<String>,
+    /// The center of each Y bin, formatted for the axis labels.
+    pub y_labels: Vec<String>,
+    /// The non-empty cells as `[x_bin, y_bin, count]` triples.
+    pub cells: Vec<[usize; 3]>,
+}
+
 /// A container for all the data and configuration needed to generate a plot.
 ///
-/// This struct is the output of the `prepare_plot_data` function and serves as the
-/// input for the `plotter` module.
-pub struct PlotData {
-    /// The title of the plot.
-    pub title: String,
-    /// A list of series to plot, each as a (name, x_series, y_series) tuple.
-    pub series_list: Vec<(String, Series, Series)>,
-    /// The special string used to identify vertical markers.
-    pub special_marker: String,
-    /// Whether to enable dynamic Y-axis rescaling on zoom.
-    pub autoscale_y: bool,
-    /// Whether to enable ECharts animations.
-    pub animations: bool,
-    /// The maximum number of decimal places for numeric tooltips.
-    pub max_decimals: i32,
-    /// Whether to use the white (light) theme.
-    pub use_white_theme: bool,
-    /// The threshold for enabling ECharts' high-performance `large` mode.
-    pub large_mode_threshold: usize,
-    /// True if any series was downsampled.
-    pub downsampled: bool,
+/// This struct is the output of the `prepare_plot_data` function and serves as the
+/// input for the `plotter` module.
+pub struct PlotData {
+    /// The title of the plot.
+    pub title: String,
+    /// Pie chart slices as (column name, value) pairs, if in pie mode.
+    pub pie_slices: Option<Vec<(String, f64)>>,
+    /// Radar chart data, if in radar mode.
+    pub radar: Option<RadarData>,
+    /// Box-and-whisker statistics, if in boxplot mode.
+    pub boxplot: Option<BoxplotData>,
+    /// The binned (x, y) counts, if in 2D heatmap mode.
+    pub heatmap_2d: Option<Heatmap2dData>,
+    /// The X-axis title, including its unit if one was given.
+    pub x_label: Option<String>,
+    /// The Y-axis title, including its unit if one was given.
+    pub y_label: Option<String>,
+    /// The number of X-axis tick intervals, or `None` for automatic.
+    pub x_tick_count: Option<usize>,
+    /// The number of Y-axis tick intervals, or `None` for automatic.
+    pub y_tick_count: Option<usize>,
+    /// The fixed lower bound of the X-axis, if any (milliseconds for datetimes).
+    pub x_min: Option<f64>,
+    /// The fixed upper bound of the X-axis, if any (milliseconds for datetimes).
+    pub x_max: Option<f64>,
+    /// The fixed lower bound of the Y-axis, overriding the padded data range.
+    pub y_min: Option<f64>,
+    /// The fixed upper bound of the Y-axis, overriding the padded data range.
+    pub y_max: Option<f64>,
+    /// Whether the X-axis is flipped to increase from right to left.
+    pub invert_x: bool,
+    /// Whether the Y-axis is flipped to increase downwards.
+    pub invert_y: bool,
+    /// Whether a numeric X-axis uses a logarithmic scale.
+    pub log_x: bool,
+    /// Whether the Y-axis uses a logarithmic scale.
+    pub log_y: bool,
+    /// The logarithm base of a logarithmic X-axis.
+    pub x_log_base: f64,
+    /// The logarithm base of a logarithmic Y-axis.
+    pub y_log_base: f64,
+    /// A list of series to plot, each as a (name, x_series, y_series) tuple.
+    pub series_list: Vec<(String, Series, Series)>,
+    /// The special string used to identify vertical markers.
+    pub special_marker: String,
+    /// The color of the vertical marker lines.
+    pub marker_color: String,
+    /// The opacity of the vertical marker lines.
+    pub marker_opacity: f64,
+    /// The width of the vertical marker lines.
+    pub marker_width: f64,
+    /// Whether to enable dynamic Y-axis rescaling on zoom.
+    pub autoscale_y: bool,
+    /// Whether to enable the interactive zoom controls.
+    pub data_zoom: bool,
+    /// Whether to remember the zoom window across page reloads.
+    pub persist_zoom: bool,
+    /// Whether to show the legend.
+    pub show_legend: bool,
+    /// Whether legend entries show the number of points of each series.
+    pub show_n_points: bool,
+    /// Glob patterns of series names that start hidden in the legend.
+    pub default_hidden: Vec<String>,
+    /// Whether to show a text box that filters the visible series by name.
+    pub series_search: bool,
+    /// The latest X value in milliseconds since the epoch, if `--show-time-since-last` is set
+    /// and the X-axis holds datetimes.
+    pub x_max_ms: Option<f64>,
+    /// Whether the tooltip is shown on click and a crosshair stays at the clicked position.
+    pub sticky_tooltip: bool,
+    /// Whether to enable the keyboard shortcuts of the HTML plot.
+    pub keyboard_shortcuts: bool,
+    /// Whether to scale point opacity inversely with local point density.
+    pub opacity_by_density: bool,
+    /// Whether to connect the points of each series with lines.
+    pub line_mode: bool,
+    /// Whether to draw each series as bars (`--chart-type bar`).
+    pub bar_mode: bool,
+    /// The step position (`start`, `middle`, or `end`) for step lines, if any.
+    pub step: Option<String>,
+    /// The stack group name that all series are stacked under, if any.
+    pub stack: Option<String>,
+    /// The default line thickness in line mode.
+    pub line_width: f64,
+    /// Per-series line thickness overrides in line mode, keyed by series name.
+    pub series_line_widths: HashMap<String, f64>,
+    /// Per-series marker shapes from `--shape-column`, keyed by series name.
+    pub series_shapes: HashMap<String, SeriesShape>,
+    /// Names of series always drawn as lines without markers, such as `--rolling-mean` trends.
+    pub line_series: HashSet<String>,
+    /// Whether to enable ECharts animations.
+    pub animations: bool,
+    /// The maximum number of decimal places for numeric tooltips.
+    pub max_decimals: i32,
+    /// Whether to use the white (light) theme.
+    pub use_white_theme: bool,
+    /// Whether to follow the OS dark/light preference instead of `use_white_theme`.
+    pub dark_mode_auto: bool,
+    /// Whether to embed the ECharts library in the page instead of loading it from a CDN.
+    pub offline: bool,
+    /// Whether time axes show UTC instead of the viewer's local time.
+    pub use_utc: bool,
+    /// The threshold for enabling ECharts' high-performance `large` mode.
+    pub large_mode_threshold: usize,
+    /// True if any series was downsampled.
+    pub downsampled: bool,
+    /// The number of sampled and total rows, if a random row sample is plotted (`--sample`).
+    pub sampled_rows: Option<(usize, usize)>,
+    /// The name of the color palette (`default` or `colorblind`).
+    pub palette: String,
+    /// The base font size for all text, if overridden.
+    pub font_size: Option<u32>,
+    /// The font family for all text, if overridden.
+    pub font_family: Option<String>,
+    /// Plot area margins as (left, right, top, bottom); `None` uses the default for that side.
+    pub grid_margins: [Option<String>; 4],
+    /// Whether each series gets its own Y-axis.
+    pub multi_yaxis: bool,
+    /// Names of series plotted against a second, right-side Y-axis (`--right-axis`).
+    pub right_axis_series: Vec<String>,
+    /// Source columns of the series renamed by `--diff` or `--cumsum`, keyed by series
+    /// name; options that name a column also apply to the series derived from it.
+    pub series_sources: HashMap<String, String>,
+    /// X-axis regions to highlight on the plot.
+    pub mark_regions: Vec<MarkRegion>,
+    /// X values of the `--mark-x` reference lines (milliseconds for datetimes).
+    pub manual_mark_x: Vec<f64>,
+    /// Y values of the `--mark-y` reference lines.
+    pub manual_mark_y: Vec<f64>,
+    /// Whether the `--mark-y` lines are drawn on every series instead of only the first.
+    pub mark_y_all_series: bool,
+}
+
+/// Builds a `PlotData` without going through the command-line interface.
+///
+/// Every option starts at the same default as the corresponding CLI flag, so a basic
+/// scatter plot only needs a title and some series, added with `add_series`. The result
+/// is rendered with `plotter::generate_html_plot`; fields without a setter can be changed
+/// on the built `PlotData` directly, since they are all public.
+pub struct PlotDataBuilder {
+    data: PlotData,
+}
+
+impl PlotDataBuilder {
+    /// Creates a builder for a plot with the given title, no series, and default options.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            data: PlotData {
+                title: title.into(),
+                pie_slices: None,
+                radar: None,
+                boxplot: None,
+                heatmap_2d: None,
+                x_label: None,
+                y_label: None,
+                x_tick_count: None,
+                y_tick_count: None,
+                x_min: None,
+                x_max: None,
+                y_min: None,
+                y_max: None,
+                invert_x: false,
+                invert_y: false,
+                log_x: false,
+                log_y: false,
+                x_log_base: 10.0,
+                y_log_base: 10.0,
+                series_list: Vec::new(),
+                special_marker: "|".to_string(),
+                marker_color: "#c23531".to_string(),
+                marker_opacity: 1.0,
+                marker_width: 2.0,
+                autoscale_y: true,
+                data_zoom: true,
+                persist_zoom: true,
+                show_legend: true,
+                show_n_points: false,
+                default_hidden: Vec::new(),
+                series_search: true,
+                x_max_ms: None,
+                sticky_tooltip: false,
+                keyboard_shortcuts: true,
+                opacity_by_density: false,
+                line_mode: false,
+                bar_mode: false,
+                step: None,
+                stack: None,
+                line_width: 1.5,
+                series_line_widths: HashMap::new(),
+                right_axis_series: Vec::new(),
+                series_sources: HashMap::new(),
+                series_shapes: HashMap::new(),
+                line_series: HashSet::new(),
+                animations: false,
+                max_decimals: 2,
+                use_white_theme: false,
+                dark_mode_auto: false,
+                offline: false,
+                use_utc: false,
+                large_mode_threshold: 2000,
+                downsampled: false,
+                sampled_rows: None,
+                palette: "default".to_string(),
+                font_size: None,
+                font_family: None,
+                grid_margins: [None, None, None, None],
+                multi_yaxis: false,
+                mark_regions: Vec::new(),
+                manual_mark_x: Vec::new(),
+                manual_mark_y: Vec::new(),
+                mark_y_all_series: false,
+            },
+        }
+    }
+
+    /// Appends a series, drawn from the paired values of `x` and `y`.
+    pub fn add_series(mut self, name: impl Into<String>, x: Series, y: Series) -> Self {
+        self.data.series_list.push((name.into(), x, y));
+        self
+    }
+
+    /// Replaces all series with the given (name, x, y) tuples.
+    pub fn series_list(mut self, series_list: Vec<(String, Series, Series)>) -> Self {
+        self.data.series_list = series_list;
+        self
+    }
+
+    /// Sets the X-axis title.
+    pub fn x_label(mut self, label: Option<String>) -> Self {
+        self.data.x_label = label;
+        self
+    }
+
+    /// Sets the Y-axis title.
+    pub fn y_label(mut self, label: Option<String>) -> Self {
+        self.data.y_label = label;
+        self
+    }
+
+    /// Fixes the X-axis bounds (milliseconds for datetimes); `None` leaves a bound automatic.
+    pub fn x_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.data.x_min = min;
+        self.data.x_max = max;
+        self
+    }
+
+    /// Fixes the Y-axis bounds; `None` leaves a bound at the padded data range.
+    pub fn y_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.data.y_min = min;
+        self.data.y_max = max;
+        self
+    }
+
+    /// Uses a logarithmic X-axis with the given base, or a linear one for `None`.
+    pub fn log_x(mut self, base: Option<f64>) -> Self {
+        self.data.log_x = base.is_some();
+        self.data.x_log_base = base.unwrap_or(10.0);
+        self
+    }
+
+    /// Uses a logarithmic Y-axis with the given base, or a linear one for `None`.
+    pub fn log_y(mut self, base: Option<f64>) -> Self {
+        self.data.log_y = base.is_some();
+        self.data.y_log_base = base.unwrap_or(10.0);
+        self
+    }
+
+    /// Connects the points of each series with lines.
+    pub fn line_mode(mut self, line_mode: bool) -> Self {
+        self.data.line_mode = line_mode;
+        self
+    }
+
+    /// Draws each series as bars.
+    pub fn bar_mode(mut self, bar_mode: bool) -> Self {
+        self.data.bar_mode = bar_mode;
+        self
+    }
+
+    /// Draws lines as a step function (`start`, `middle`, or `end`).
+    pub fn step(mut self, step: Option<String>) -> Self {
+        self.data.step = step;
+        self
+    }
+
+    /// Stacks all series under the given stack group name.
+    pub fn stack(mut self, stack: Option<String>) -> Self {
+        self.data.stack = stack;
+        self
+    }
+
+    /// Sets the line thickness of all series in line mode.
+    pub fn line_width(mut self, width: f64) -> Self {
+        self.data.line_width = width;
+        self
+    }
+
+    /// Enables dynamic Y-axis rescaling on zoom.
+    pub fn autoscale_y(mut self, autoscale_y: bool) -> Self {
+        self.data.autoscale_y = autoscale_y;
+        self
+    }
+
+    /// Enables the interactive zoom controls.
+    pub fn data_zoom(mut self, data_zoom: bool) -> Self {
+        self.data.data_zoom = data_zoom;
+        self
+    }
+
+    /// Shows the legend.
+    pub fn show_legend(mut self, show_legend: bool) -> Self {
+        self.data.show_legend = show_legend;
+        self
+    }
+
+    /// Uses the white (light) theme instead of the dark one.
+    pub fn white_theme(mut self, white_theme: bool) -> Self {
+        self.data.use_white_theme = white_theme;
+        self
+    }
+
+    /// Sets the color palette (`default` or `colorblind`).
+    pub fn palette(mut self, palette: impl Into<String>) -> Self {
+        self.data.palette = palette.into();
+        self
+    }
+
+    /// Sets the maximum number of decimal places in tooltips.
+    pub fn max_decimals(mut self, max_decimals: i32) -> Self {
+        self.data.max_decimals = max_decimals;
+        self
+    }
+
+    /// Sets the number of points above which ECharts' `large` mode is used.
+    pub fn large_mode_threshold(mut self, threshold: usize) -> Self {
+        self.data.large_mode_threshold = threshold;
+        self
+    }
+
+    /// Gives each series its own Y-axis.
+    pub fn multi_yaxis(mut self, multi_yaxis: bool) -> Self {
+        self.data.multi_yaxis = multi_yaxis;
+        self
+    }
+
+    /// Marks the plot as downsampled, which is noted on the page.
+    pub fn downsampled(mut self, downsampled: bool) -> Self {
+        self.data.downsampled = downsampled;
+        self
+    }
+
+    /// Returns the configured `PlotData`.
+    pub fn build(self) -> PlotData {
+        self.data
+    }
+}
+
+/// Selects the X and Y series from a DataFrame and packages them for plotting.
+///
+/// This function encapsulates the core logic for interpreting user intent from the CLI
+/// arguments and applying it to the loaded data.
+///
+/// # Arguments
+///
+/// * `df` - The input `DataFrame` loaded from a file.
+/// * `cli` - A reference to the parsed command-line arguments (`Cli` struct).
+/// * `file_path` - The path of the input file, used to generate a default title.
+///
+/// # Returns
+///
+/// A `Result` containing a `PlotData` struct ready for the plotting engine,
+/// or an `AppError` if an appropriate X or Y series cannot be determined.
+pub fn prepare_plot_data(
+    mut df: DataFrame,
+    cli: &Cli,
+    file_path: &Path,
+) -> Result<PlotData, AppError> {
+    // Plot a random sample of the rows, if requested.
+    let mut sampled_rows = None;
+    if let Some(n) = cli.sample {
+        let total_rows = df.height();
+        df = sample_dataframe(df, n, cli.seed)?;
+        if df.height() < total_rows {
+            status!("  -> Sampled {} of {} rows", df.height(), total_rows);
+            sampled_rows = Some((df.height(), total_rows));
+        }
+    }
+
+    // Rename columns first, so the column selection options can refer to the new names.
+    for (old, new) in &cli.rename {
+        if df.column(old).is_ok() {
+            df.rename(old, new.as_str().into())?;
+        } else if cli.strict {
+            return Err(AppError::ColumnNotFound(old.clone()));
+        } else {
+            status!("  -> Warning: Column '{}' to rename was not found.", old);
+        }
+    }
+
+    // 0. Add computed columns first, so they can be selected as X or Y.
+    for (name, expression) in &cli.expression_column {
+        add_expression_column(&mut df, name, expression)?;
+    }
+
+    // Resolve column indices in --columns to names, and check all requested Y columns
+    // up front, so every typo is reported at once.
+    let y_columns = match &cli.columns {
+        Some(specs) => {
+            let names = resolve_column_specs(&df, specs);
+            validate_columns(&df, &names)?;
+            Some(names)
+        }
+        None => None,
+    };
+    // Excluded columns are checked too, so a typo does not silently plot the column.
+    validate_columns(&df, &cli.exclude_columns)?;
+
+    // 1. Determine the X-axis (index) series: a composite of several columns if requested,
+    //    otherwise based on priority.
+    let multi_index = match &cli.multi_index {
+        Some(columns) => {
+            let name = create_multi_index(&mut df, columns, &cli.multi_index_sep)?;
+            // The source columns are represented by the composite X-axis and not plotted.
+            df = df.drop_many(columns.iter().map(|c| c.as_str()));
+            Some(name)
+        }
+        None => None,
+    };
+    let (mut x_series, x_name) = match &multi_index {
+        Some(name) => (
+            df.column(name)?.as_materialized_series().clone(),
+            name.clone(),
+        ),
+        None => select_x_series(&df, cli)?,
+    };
+
+    // Sort the rows, if requested, so lines and downsampling follow the sorted order.
+    // Row-number X-axes are not DataFrame columns and are already in order.
+    if let Some(sort_by) = &cli.sort_by {
+        let sort_column = if sort_by == SORT_BY_X {
+            &x_name
+        } else {
+            sort_by
+        };
+        let x_is_column = df.column(&x_name).is_ok();
+        if sort_by != SORT_BY_X || x_is_column {
+            validate_columns(&df, std::slice::from_ref(sort_column))?;
+            df = df.sort(
+                [sort_column.as_str()],
+                SortMultipleOptions::default()
+                    .with_order_descending(cli.sort_desc)
+                    .with_nulls_last(true),
+            )?;
+            if x_is_column {
+                x_series = df.column(&x_name)?.as_materialized_series().clone();
+            }
+        }
+    }
+
+    if cli.debug {
+        status!(
+            "  -> Selected X-axis column: '{}' with {} values",
+            x_name,
+            x_series.len()
+        );
+    }
+    if cli.print_x_range || cli.debug {
+        print_x_range(&x_series);
+    }
+
+    // 2. Determine the Y-axis series.
+    let mut y_series_list = select_y_series(&df, cli, y_columns.as_deref(), &x_name)?;
+
+    // Treat NaN and infinite float values as missing, if requested.
+    if cli.nan_to_null || cli.inf_to_null {
+        let kind = match (cli.nan_to_null, cli.inf_to_null) {
+            (true, true) => "NaN or infinite",
+            (true, false) => "NaN",
+            _ => "infinite",
+        };
+        for y in y_series_list.iter_mut() {
+            let mut cleaned = y.clone();
+            if cli.nan_to_null {
+                cleaned = replace_nans_with_null(&cleaned)?;
+            }
+            if cli.inf_to_null {
+                cleaned = replace_infs_with_null(&cleaned)?;
+            }
+            let replaced = cleaned.null_count() - y.null_count();
+            if cli.debug && replaced > 0 {
+                status!(
+                    "  -> Replaced {} {} values with null in '{}'",
+                    replaced,
+                    kind,
+                    y.name()
+                );
+            }
+            *y = cleaned;
+        }
+    }
+
+    // Series renamed by --diff or --cumsum, mapped to their source column, so that
+    // options naming a column (e.g., --right-axis) still apply to them.
+    let mut series_sources: HashMap<String, String> = HashMap::new();
+    // With --group-by, differences and running totals are taken within each group.
+    let mut groups = match &cli.group_by {
+        Some(group_column) if cli.diff || cli.cumsum => Some(
+            df.column(group_column)
+                .map_err(|_| AppError::ColumnNotFound(group_column.clone()))?
+                .as_materialized_series()
+                .cast(&DataType::String)?,
+        ),
+        _ => None,
+    };
+
+    // Replace each Y series with its first-order difference (`<name>_diff`), if
+    // requested. Rows without a predecessor (the first row, or the first row of each
+    // group) are dropped from the X-axis, all series, and the DataFrame, which the group,
+    // shape, and heatmap columns are taken from later.
+    if cli.diff {
+        let previous = previous_rows(groups.as_ref(), df.height())?;
+        let has_previous = previous.is_not_null();
+        for y in y_series_list.iter_mut() {
+            *y = if y.dtype().is_primitive_numeric() {
+                let mut difference = first_difference(y, &previous)?.filter(&has_previous)?;
+                rename_with_suffix(&mut difference, "diff", &mut series_sources);
+                difference
+            } else {
+                y.filter(&has_previous)?
+            };
+        }
+        x_series = x_series.filter(&has_previous)?;
+        df = df.filter(&has_previous)?;
+        groups = groups.map(|g| g.filter(&has_previous)).transpose()?;
+    }
+
+    // Replace each Y series with its running total (`<name>_cumsum`), if requested.
+    if cli.cumsum {
+        if cli.diff {
+            status!("  -> Warning: --cumsum after --diff reproduces the original values minus the first row.");
+        }
+        for y in y_series_list.iter_mut() {
+            if y.dtype().is_primitive_numeric() {
+                *y = match &groups {
+                    Some(groups) => cum_sum_by_group(y, groups)?,
+                    None => cum_sum(y, false)?,
+                };
+                rename_with_suffix(y, "cumsum", &mut series_sources);
+            }
+        }
+    }
+
+    // Take the magnitude of each Y series, if requested.
+    if cli.abs {
+        for y in y_series_list.iter_mut() {
+            if y.dtype().is_primitive_numeric() {
+                *y = abs(y)?;
+            }
+        }
+    }
+
+    // Rescale each Y series to [0, 1], if requested.
+    if cli.normalize {
+        for y in y_series_list.iter_mut() {
+            *y = normalize_series(y);
+        }
+    }
+
+    // Standardize each Y series to zero mean and unit variance, if requested.
+    if cli.z_score {
+        for y in y_series_list.iter_mut() {
+            *y = zscore_series(y)?;
+        }
+    }
+
+    if cli.debug {
+        for series in std::iter::once(&x_series).chain(y_series_list.iter()) {
+            if exceeds_safe_integer_range(series) {
+                status!(
+                    "  -> Warning: Column '{}' has values beyond +/-2^53; they will lose precision when plotted.",
+                    series.name()
+                );
+            }
+        }
+    }
+
+    // In pie mode, reduce each Y column to a single slice value.
+    let pie_slices = cli.pie.then(|| {
+        y_series_list
+            .iter()
+            .filter_map(|y| aggregate_series(y, &cli.pie_agg).map(|v| (y.name().to_string(), v)))
+            .collect::<Vec<_>>()
+    });
+
+    // In radar mode, take the first rows of every numeric Y column.
+    let radar = cli
+        .radar
+        .then(|| build_radar_data(&x_series, &y_series_list, cli.radar_max_series));
+
+    // In boxplot mode, summarize each Y column.
+    let boxplot = if cli.boxplot {
+        let mut boxes = Vec::new();
+        for y in &y_series_list {
+            if let Some(stats) = five_number_summary(y)? {
+                boxes.push((y.name().to_string(), stats));
+            }
+        }
+        Some(BoxplotData { boxes })
+    } else {
+        None
+    };
+
+    // In 2D heatmap mode, bin the (x, y) pairs of the chosen columns into a grid.
+    let heatmap_2d = if cli.heatmap_2d {
+        let mut columns = Vec::new();
+        columns.extend(cli.heatmap_x_col.clone());
+        columns.extend(cli.heatmap_y_col.clone());
+        validate_columns(&df, &columns)?;
+        let hx = match &cli.heatmap_x_col {
+            Some(name) => df.column(name)?.as_materialized_series().clone(),
+            None => x_series.clone(),
+        };
+        let hy = match &cli.heatmap_y_col {
+            Some(name) => df.column(name)?.as_materialized_series().clone(),
+            None => y_series_list
+                .first()
+                .cloned()
+                .ok_or(AppError::NoNumericColumns)?,
+        };
+        Some(build_heatmap_2d(
+            &hx,
+            &hy,
+            cli.heatmap_bins_x.max(1),
+            cli.heatmap_bins_y.max(1),
+        ))
+    } else {
+        None
+    };
+
+    // Pair each Y series with the X series, splitting by category if requested.
+    let mut series_pairs: Vec<SeriesEntry> = y_series_list
+        .into_iter()
+        .map(|y_series| (y_series.name().to_string(), x_series.clone(), y_series))
+        .collect();
+    let mut series_shapes = HashMap::new();
+    if let Some(shape_column) = &cli.shape_column {
+        (series_pairs, series_shapes) = split_by_shape_column(&df, series_pairs, shape_column)?;
+    }
+    if let Some(group_column) = &cli.group_by {
+        series_pairs = split_by_group_column(&df, series_pairs, group_column, cli.max_groups)?;
+    }
+
+    // Aggregate each series into regular time buckets, if requested.
+    if let Some(every_ms) = cli.resample {
+        let mut resampled = Vec::with_capacity(series_pairs.len());
+        for (name, x_series, y_series) in series_pairs {
+            let (x_resampled, mut y_resampled) = resample_series(&x_series, &y_series, every_ms)?;
+            if cli.resample_ffill {
+                y_resampled = y_resampled.fill_null(FillNullStrategy::Forward(None))?;
+            }
+            if cli.resample_bfill {
+                y_resampled = y_resampled.fill_null(FillNullStrategy::Backward(None))?;
+            }
+            resampled.push((name, x_resampled, y_resampled));
+        }
+        series_pairs = resampled;
+    } else if cli.resample_ffill || cli.resample_bfill {
+        status!("  -> Warning: --resample-ffill and --resample-bfill have no effect without --resample.");
+    }
+
+    // Replace each series with its empirical CDF, if requested.
+    if cli.ecdf {
+        series_pairs = series_pairs
+            .into_iter()
+            .map(|(name, _, y_series)| {
+                let (values, probabilities) = compute_ecdf(&y_series);
+                (name, values, probabilities)
+            })
+            .collect();
+    }
+
+    // Replace each series with its lagged copy plotted against itself, if requested.
+    if let Some(lag) = cli.lag_plot {
+        series_pairs = series_pairs
+            .into_iter()
+            .map(|(name, _, y_series)| {
+                let (x_lagged, y_lagged) = compute_lag_plot(&y_series, lag);
+                (format!("{}_t+{}", name, lag), x_lagged, y_lagged)
+            })
+            .collect();
+    }
+
+    // Default axis titles; an ECDF plots values against cumulative probability,
+    // and a lag plot plots each value against the one `lag` rows later.
+    let (default_x_label, default_y_label) = if cli.ecdf {
+        let x_label = match series_pairs.as_slice() {
+            [(name, _, _)] => name.clone(),
+            _ => "Value".to_string(),
+        };
+        (Some(x_label), Some("Probability".to_string()))
+    } else if let Some(lag) = cli.lag_plot {
+        match series_pairs.as_slice() {
+            [(name, x_series, _)] => (Some(x_series.name().to_string()), Some(name.clone())),
+            _ => (Some("y(t)".to_string()), Some(format!("y(t+{})", lag))),
+        }
+    } else {
+        (multi_index, None)
+    };
+
+    if let Some(mode) = &cli.sort_series_by {
+        sort_series(&mut series_pairs, mode);
+    }
+
+    let mut final_series_list = Vec::new();
+    let mut line_series = HashSet::new();
+    let mut right_axis_series = Vec::new();
+    let mut downsampled = false;
+
+    // 3. Process each series, applying downsampling and X-axis clipping if necessary.
+    for (y_name, mut x_series, mut y_series) in series_pairs {
+        // Smooth the full-resolution data, before it is downsampled. Marker columns
+        // have no values to smooth.
+        let trend = match cli.rolling_mean {
+            Some(window) if y_series.dtype().is_primitive_numeric() => Some((
+                format!("{}_ma{}", y_name, window),
+                x_series.clone(),
+                rolling_mean(&y_series, window)?,
+            )),
+            _ => None,
+        };
+
+        if y_series.len() > cli.downsample_threshold {
+            status!(
+                "  -> Downsampling '{}' from {} to {} points...",
+                y_name,
+                y_series.len(),
+                cli.downsample_threshold
+            );
+            (x_series, y_series) =
+                downsample_series(&x_series, &y_series, cli.downsample_threshold)?;
+            downsampled = true;
+        }
+
+        if let Some(p) = cli.clip_x {
+            (x_series, y_series) = clip_x_percentile(&x_series, &y_series, p)?;
+        }
+
+        // Trend lines go on the same axis as their series.
+        let source = series_sources.get(&y_name).unwrap_or(&y_name);
+        let right_axis = cli.right_axis.contains(&y_name) || cli.right_axis.contains(source);
+        if right_axis {
+            right_axis_series.push(y_name.clone());
+        }
+        final_series_list.push((y_name, x_series, y_series));
+
+        if let Some((trend_name, mut trend_x, mut trend_y)) = trend {
+            let threshold = cli.downsample_threshold * TREND_DOWNSAMPLE_FACTOR;
+            if trend_y.len() > threshold {
+                (trend_x, trend_y) = downsample_series(&trend_x, &trend_y, threshold)?;
+                downsampled = true;
+            }
+            if let Some(p) = cli.clip_x {
+                (trend_x, trend_y) = clip_x_percentile(&trend_x, &trend_y, p)?;
+            }
+            line_series.insert(trend_name.clone());
+            if right_axis {
+                right_axis_series.push(trend_name.clone());
+            }
+            final_series_list.push((trend_name, trend_x, trend_y));
+        }
+    }
+
+    for name in &cli.right_axis {
+        let matched = right_axis_series
+            .iter()
+            .any(|series| series == name || series_sources.get(series) == Some(name));
+        if !matched {
+            status!(
+                "  -> Warning: Series '{}' in --right-axis was not found among the plotted series.",
+                name
+            );
+        }
+    }
+
+    if cli.multi_yaxis && final_series_list.len() > MAX_Y_AXES {
+        status!(
+            "  -> Warning: {} series requested separate Y-axes, but at most {} are supported. Extra series share the last axis.",
+            final_series_list.len(),
+            MAX_Y_AXES
+        );
+    }
+
+    let show_legend = !cli.no_legend && (!cli.auto_hide_legend || final_series_list.len() != 1);
+
+    let x_max_ms = if cli.show_time_since_last {
+        let x_max_ms = latest_datetime_ms(&final_series_list);
+        if x_max_ms.is_none() {
+            status!(
+                "  -> Warning: --show-time-since-last requires a datetime X-axis; ignoring it."
+            );
+        }
+        x_max_ms
+    } else {
+        None
+    };
+
+    // 4. Load the highlighted regions, if requested.
+    let mark_regions = match &cli.mark_regions {
+        Some(path) => load_mark_regions(path, cli)?,
+        None => Vec::new(),
+    };
+
+    let line_mode = cli.line
+        || cli.step.is_some()
+        || cli.ecdf
+        || matches!(cli.chart_type.as_str(), "line" | "step");
+
+    // Explicit Y bounds replace the dynamic range, so they take priority over autoscaling
+    let fixed_y_range = cli.y_min.is_some() || cli.y_max.is_some();
+    let autoscale_y = !cli.no_autoscale_y && !cli.no_data_zoom && cli.stack.is_none() && !cli.log_y;
+    if autoscale_y && fixed_y_range && cli.debug {
+        status!("  -> Warning: --y-min/--y-max disable Y-axis autoscaling.");
+    }
+    let autoscale_y = autoscale_y && !fixed_y_range;
+
+    // 5. Determine the plot title.
+    let column_title = cli
+        .title_from_column
+        .as_ref()
+        .and_then(|name| first_text_value(&df, name));
+    let title = cli.title.clone().or(column_title).unwrap_or_else(|| {
+        if data_loader::is_stdin(file_path) {
+            return "stdin".to_string();
+        }
+        file_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    });
+
+    // Explicit line options take precedence over `--chart-type bar`
+    let bar_mode = cli.chart_type == "bar" && !line_mode;
+    let step = cli
+        .step
+        .clone()
+        .or_else(|| (cli.ecdf || cli.chart_type == "step").then(|| "end".to_string()));
+    let palette = if cli.colorblind_safe {
+        "colorblind".to_string()
+    } else {
+        cli.palette.clone()
+    };
+    let plot_data = PlotDataBuilder::new(title)
+        .series_list(final_series_list)
+        .x_label(axis_label(cli.x_axis_label.as_deref(), cli.x_unit.as_deref()).or(default_x_label))
+        .y_label(axis_label(cli.y_axis_label.as_deref(), cli.y_unit.as_deref()).or(default_y_label))
+        .x_range(cli.x_min, cli.x_max)
+        .y_range(cli.y_min, cli.y_max)
+        .log_x(cli.log_x.then_some(cli.x_log_base))
+        .log_y(cli.log_y.then_some(cli.y_log_base))
+        .line_mode(line_mode)
+        .bar_mode(bar_mode)
+        .step(step)
+        .stack(cli.stack.clone())
+        .line_width(cli.line_width)
+        // Autoscaling works on individual series values, which do not match stacked totals.
+        // Autoscaling pads the range linearly, which can go below zero on a log axis.
+        .autoscale_y(autoscale_y)
+        .data_zoom(!cli.no_data_zoom)
+        .show_legend(show_legend)
+        .white_theme(cli.white_theme)
+        .palette(palette)
+        .max_decimals(cli.max_decimals)
+        .large_mode_threshold(cli.large_mode_threshold)
+        .multi_yaxis(cli.multi_yaxis)
+        .downsampled(downsampled)
+        .build();
+
+    // The remaining options are specific to the command-line interface
+    Ok(PlotData {
+        pie_slices,
+        radar,
+        boxplot,
+        heatmap_2d,
+        x_tick_count: cli.x_tick_count,
+        y_tick_count: cli.y_tick_count,
+        invert_x: cli.invert_x,
+        invert_y: cli.invert_y,
+        special_marker: cli.vertical_marker.clone(),
+        marker_color: cli.marker_color.clone(),
+        marker_opacity: cli.marker_opacity,
+        marker_width: cli.marker_width,
+        persist_zoom: !cli.no_data_zoom && !cli.no_persist_zoom,
+        show_n_points: cli.show_n_points,
+        default_hidden: cli.default_hidden.clone(),
+        series_search: show_legend && !cli.no_series_search,
+        x_max_ms,
+        sticky_tooltip: cli.sticky_tooltip,
+        keyboard_shortcuts: !cli.no_keyboard_shortcuts,
+        opacity_by_density: cli.scatter_opacity_by_density,
+        series_line_widths: cli.series_line_width.iter().cloned().collect(),
+        right_axis_series,
+        series_sources,
+        series_shapes,
+        line_series,
+        animations: cli.animations,
+        dark_mode_auto: cli.dark_mode_auto,
+        offline: cli.offline,
+        // Converted datetimes hold the target wall-clock time, which must not be shifted again.
+        use_utc: cli.convert_tz.is_some(),
+        font_size: cli.font_size,
+        font_family: cli.font_family.clone(),
+        grid_margins: [
+            cli.grid_left.clone(),
+            cli.grid_right.clone(),
+            cli.grid_top.clone(),
+            cli.grid_bottom.clone(),
+        ],
+        mark_regions,
+        manual_mark_x: cli.mark_x.clone(),
+        manual_mark_y: cli.mark_y.clone(),
+        mark_y_all_series: cli.mark_applies_to_all,
+        sampled_rows,
+        ..plot_data
+    })
+}
+
+/// A token of an `--expression-column` expression.
+#[derive(Debug, Clone, PartialEq)]
+enum ExprToken {
+    Number(f64),
+    Column(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+/// Evaluates an arithmetic expression over the columns of a DataFrame and adds the
+/// result as a new `Float64` column named `name`.
+///
+/// Expressions support `+`, `-`, `*`, `/`, `**` (power), unary minus, parentheses,
+/// numeric literals, and column names made of letters, digits, `_`, and `.`.
+/// Operations involving a null value produce null.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidExpression` if the expression cannot be parsed, or
+/// `AppError::ColumnNotFound` if it references a missing column.
+pub fn add_expression_column(
+    df: &mut DataFrame,
+    name: &str,
+    expression: &str,
+) -> Result<(), AppError> {
+    let invalid =
+        |reason: &str| AppError::InvalidExpression(expression.to_string(), reason.to_string());
+    let tokens = tokenize_expression(expression).map_err(|e| invalid(&e))?;
+    let mut pos = 0;
+    let mut result = parse_sum(&tokens, &mut pos, df, expression)?;
+    if pos < tokens.len() {
+        return Err(invalid(&format!("unexpected {:?}", tokens[pos])));
+    }
+    result.rename(name.into());
+    df.with_column(result)?;
+    Ok(())
+}
+
+/// Splits an expression into numbers, column names, operators, and parentheses.
+fn tokenize_expression(expression: &str) -> Result<Vec<ExprToken>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal
+                .parse()
+                .map_err(|_| format!("invalid number '{}'", literal))?;
+            tokens.push(ExprToken::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            tokens.push(ExprToken::Column(chars[start..i].iter().collect()));
+        } else {
+            let token = match c {
+                '*' if chars.get(i + 1) == Some(&'*') => {
+                    i += 1;
+                    ExprToken::Op("**")
+                }
+                '+' => ExprToken::Op("+"),
+                '-' => ExprToken::Op("-"),
+                '*' => ExprToken::Op("*"),
+                '/' => ExprToken::Op("/"),
+                '(' => ExprToken::LParen,
+                ')' => ExprToken::RParen,
+                _ => return Err(format!("unexpected character '{}'", c)),
+            };
+            tokens.push(token);
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses and evaluates `product (('+' | '-') product)*`.
+fn parse_sum(
+    tokens: &[ExprToken],
+    pos: &mut usize,
+    df: &DataFrame,
+    expression: &str,
+) -> Result<Series, AppError> {
+    let mut left = parse_product(tokens, pos, df, expression)?;
+    while let Some(ExprToken::Op(op @ ("+" | "-"))) = tokens.get(*pos) {
+        *pos += 1;
+        let right = parse_product(tokens, pos, df, expression)?;
+        left = if *op == "+" {
+            (&left + &right)?
+        } else {
+            (&left - &right)?
+        };
+    }
+    Ok(left)
+}
+
+/// Parses and evaluates `unary (('*' | '/') unary)*`.
+fn parse_product(
+    tokens: &[ExprToken],
+    pos: &mut usize,
+    df: &DataFrame,
+    expression: &str,
+) -> Result<Series, AppError> {
+    let mut left = parse_unary(tokens, pos, df, expression)?;
+    while let Some(ExprToken::Op(op @ ("*" | "/"))) = tokens.get(*pos) {
+        *pos += 1;
+        let right = parse_unary(tokens, pos, df, expression)?;
+        left = if *op == "*" {
+            (&left * &right)?
+        } else {
+            (&left / &right)?
+        };
+    }
+    Ok(left)
+}
+
+/// Parses and evaluates `'-' unary | power`, so that `-a ** 2` is `-(a ** 2)`.
+fn parse_unary(
+    tokens: &[ExprToken],
+    pos: &mut usize,
+    df: &DataFrame,
+    expression: &str,
+) -> Result<Series, AppError> {
+    if tokens.get(*pos) == Some(&ExprToken::Op("-")) {
+        *pos += 1;
+        let operand = parse_unary(tokens, pos, df, expression)?;
+        return Ok((&operand * -1.0).with_name(operand.name().clone()));
+    }
+    parse_power(tokens, pos, df, expression)
+}
+
+/// Parses and evaluates `primary ('**' unary)?`; the power operator is right-associative.
+fn parse_power(
+    tokens: &[ExprToken],
+    pos: &mut usize,
+    df: &DataFrame,
+    expression: &str,
+) -> Result<Series, AppError> {
+    let base = parse_primary(tokens, pos, df, expression)?;
+    if tokens.get(*pos) != Some(&ExprToken::Op("**")) {
+        return Ok(base);
+    }
+    *pos += 1;
+    let exponent = parse_unary(tokens, pos, df, expression)?;
+    let powered: Float64Chunked = base
+        .f64()?
+        .into_iter()
+        .zip(exponent.f64()?)
+        .map(|(b, e)| Some(b?.powf(e?)))
+        .collect();
+    Ok(powered.with_name(base.name().clone()).into_series())
+}
+
+/// Parses and evaluates `number | column | '(' sum ')'`.
+///
+/// Numbers are broadcast to a constant series with one value per row, and columns
+/// are cast to `Float64`.
+fn parse_primary(
+    tokens: &[ExprToken],
+    pos: &mut usize,
+    df: &DataFrame,
+    expression: &str,
+) -> Result<Series, AppError> {
+    let invalid =
+        |reason: &str| AppError::InvalidExpression(expression.to_string(), reason.to_string());
+    let token = tokens
+        .get(*pos)
+        .ok_or_else(|| invalid("unexpected end of expression"))?;
+    *pos += 1;
+    match token {
+        ExprToken::Number(v) => Ok(Series::new("literal".into(), vec![*v; df.height()])),
+        ExprToken::Column(name) => Ok(df
+            .column(name)
+            .map_err(|_| AppError::ColumnNotFound(name.clone()))?
+            .as_materialized_series()
+            .cast(&DataType::Float64)?),
+        ExprToken::LParen => {
+            let inner = parse_sum(tokens, pos, df, expression)?;
+            if tokens.get(*pos) != Some(&ExprToken::RParen) {
+                return Err(invalid("missing ')'"));
+            }
+            *pos += 1;
+            Ok(inner)
+        }
+        other => Err(invalid(&format!("unexpected {:?}", other))),
+    }
+}
+
+/// Splits a DataFrame into consecutive chunks of at most `chunk_size` rows.
+///
+/// Each chunk starts `chunk_size - overlap` rows after the previous one, so consecutive
+/// chunks share `overlap` rows. An overlap of `chunk_size` or more is reduced so that
+/// every chunk advances by at least one row. An empty DataFrame yields a single empty chunk.
+pub fn split_dataframe_into_chunks(
+    df: DataFrame,
+    chunk_size: usize,
+    overlap: usize,
+) -> Vec<DataFrame> {
+    let chunk_size = chunk_size.max(1);
+    let step = chunk_size.saturating_sub(overlap).max(1);
+    if df.height() <= chunk_size {
+        return vec![df];
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        chunks.push(df.slice(start as i64, chunk_size));
+        if start + chunk_size >= df.height() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+/// Merges the plots of several input files into one comparative plot (`--combine`).
+///
+/// Each `(source, plot)` pair contributes its series, renamed to `<source>: <name>` so
+/// that same-named columns of different files stay apart. All other settings are taken
+/// from the first plot, and distinct titles are joined with commas. Plots whose X-axis
+/// type differs from the first plot's cannot share its X-axis and are skipped with a warning.
+///
+/// Returns `None` if `plots` is empty.
+pub fn merge_plot_data(plots: Vec<(String, PlotData)>) -> Option<PlotData> {
+    let mut plots = plots.into_iter();
+    let (first_source, mut merged) = plots.next()?;
+    prefix_series_names(&mut merged, &first_source);
+    let x_kind = x_axis_kind(&merged);
+    let mut titles = vec![merged.title.clone()];
+
+    for (source, mut plot) in plots {
+        if x_axis_kind(&plot) != x_kind {
+            status!(
+                "  -> Warning: Skipping '{}' in the combined plot: its X-axis type differs from '{}'",
+                source, first_source
+            );
+            continue;
+        }
+        prefix_series_names(&mut plot, &source);
+        // Keep the colors of shape-split series distinct from those of earlier files
+        let group_offset = merged.series_list.len();
+        for shape in plot.series_shapes.values_mut() {
+            shape.color_group += group_offset;
+        }
+        merged.series_list.extend(plot.series_list);
+        merged.series_line_widths.extend(plot.series_line_widths);
+        merged.right_axis_series.extend(plot.right_axis_series);
+        merged.series_sources.extend(plot.series_sources);
+        merged.series_shapes.extend(plot.series_shapes);
+        merged.line_series.extend(plot.line_series);
+        merged.downsampled |= plot.downsampled;
+        merged.sampled_rows = match (merged.sampled_rows, plot.sampled_rows) {
+            (Some((a, b)), Some((c, d))) => Some((a + c, b + d)),
+            (a, b) => a.or(b),
+        };
+        merged.x_max_ms = match (merged.x_max_ms, plot.x_max_ms) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        if !titles.contains(&plot.title) {
+            titles.push(plot.title);
+        }
+    }
+    merged.title = titles.join(", ");
+    Some(merged)
+}
+
+/// Prefixes the names of a plot's series, and the per-series settings keyed by them,
+/// with `<source>: `.
+fn prefix_series_names(plot: &mut PlotData, source: &str) {
+    let prefixed = |name: &str| format!("{}: {}", source, name);
+    for (name, _, _) in plot.series_list.iter_mut() {
+        *name = prefixed(name);
+    }
+    plot.series_line_widths = std::mem::take(&mut plot.series_line_widths)
+        .into_iter()
+        .map(|(name, width)| (prefixed(&name), width))
+        .collect();
+    for name in plot.right_axis_series.iter_mut() {
+        *name = prefixed(name);
+    }
+    plot.series_sources = std::mem::take(&mut plot.series_sources)
+        .into_iter()
+        .map(|(name, source)| (prefixed(&name), prefixed(&source)))
+        .collect();
+    plot.series_shapes = std::mem::take(&mut plot.series_shapes)
+        .into_iter()
+        .map(|(name, shape)| (prefixed(&name), shape))
+        .collect();
+    plot.line_series = std::mem::take(&mut plot.line_series)
+        .into_iter()
+        .map(|name| prefixed(&name))
+        .collect();
+}
+
+/// Classifies the X-axis of a plot as `time`, `category`, or `value`, judging by its first series.
+fn x_axis_kind(plot: &PlotData) -> Option<&'static str> {
+    plot.series_list.first().map(|(_, x, _)| match x.dtype() {
+        DataType::Datetime(_, _) | DataType::Date => "time",
+        DataType::String => "category",
+        _ => "value",
+    })
+}
+
+/// Keeps only the rows of a DataFrame for which a SQL condition holds (`--filter`).
+///
+/// The condition is parsed as a SQL expression by Polars (e.g., `temp > 100 AND status = 'on'`).
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidFilter` if the condition cannot be parsed or evaluated,
+/// for example when it refers to a column that does not exist.
+pub fn filter_rows(df: DataFrame, condition: &str) -> Result<DataFrame, AppError> {
+    let invalid = |e: PolarsError| AppError::InvalidFilter(condition.to_string(), e.to_string());
+    let predicate = polars::sql::sql_expr(condition).map_err(invalid)?;
+    df.lazy().filter(predicate).collect().map_err(invalid)
+}
+
+/// Replaces the audio channels of a DataFrame with their magnitude spectrum (`--fft`).
+///
+/// Each column other than `sample_index` is transformed with a forward FFT over all
+/// of its samples. The result has a `frequency_hz` column, from 0 Hz up to the Nyquist
+/// frequency, and one column per channel holding the magnitudes divided by the number
+/// of samples. Missing samples are treated as silence.
+///
+/// # Errors
+///
+/// Returns an error if a channel cannot be converted to floating point.
+pub fn compute_fft_dataframe(df: &DataFrame, sample_rate: u32) -> Result<DataFrame, AppError> {
+    let n = df.height();
+    if n == 0 {
+        return Ok(df.clone());
+    }
+    let bins = n / 2 + 1;
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(n);
+
+    let frequencies: Vec<f64> = (0..bins)
+        .map(|k| k as f64 * sample_rate as f64 / n as f64)
+        .collect();
+    let mut columns = vec![Column::new("frequency_hz".into(), frequencies)];
+    for column in df.get_columns() {
+        if column.name() == "sample_index" {
+            continue;
+        }
+        let samples = column.cast(&DataType::Float32)?;
+        let mut buffer: Vec<Complex<f32>> = samples
+            .f32()?
+            .iter()
+            .map(|v| Complex::new(v.unwrap_or(0.0), 0.0))
+            .collect();
+        fft.process(&mut buffer);
+        let magnitudes: Vec<f32> = buffer[..bins].iter().map(|c| c.norm() / n as f32).collect();
+        columns.push(Column::new(column.name().clone(), magnitudes));
+    }
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Replaces the `sample_index` column of an audio DataFrame with a `time_seconds`
+/// column, dividing each index by the sample rate (`--audio-time-axis`).
+pub fn sample_index_to_seconds(df: &mut DataFrame, sample_rate: u32) -> Result<(), AppError> {
+    let seconds = df
+        .column("sample_index")?
+        .cast(&DataType::Float64)?
+        .f64()?
+        .apply_values(|i| i / sample_rate as f64)
+        .into_series()
+        .with_name("time_seconds".into());
+    df.replace("sample_index", seconds)?;
+    Ok(())
+}
+
+/// Randomly samples `n` rows of a DataFrame without replacement, keeping their order.
+///
+/// The same `seed` always selects the same rows. DataFrames with at most `n` rows are
+/// returned unchanged.
+pub fn sample_dataframe(df: DataFrame, n: usize, seed: u64) -> Result<DataFrame, AppError> {
+    if df.height() <= n {
+        return Ok(df);
+    }
+    let rows = IdxCa::from_vec("rows".into(), (0..df.height() as IdxSize).collect());
+    let sampled = rows.sample_n(n, false, false, Some(seed))?.sort(false);
+    Ok(df.take(&sampled)?)
+}
+
+/// Adds a composite string column built by joining the values of several columns.
+///
+/// The new column is named after the source columns joined with `_` (e.g., `date_hour`),
+/// and each value is the row's values joined with `sep` (see `format_index_value`).
+/// Rows where any of the source columns is null are null.
+///
+/// # Returns
+///
+/// The name of the new column.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFound` if any of the columns does not exist.
+pub fn create_multi_index(
+    df: &mut DataFrame,
+    cols: &[String],
+    sep: &str,
+) -> Result<String, AppError> {
+    let mut parts = Vec::with_capacity(cols.len());
+    for name in cols {
+        let column = df
+            .column(name)
+            .map_err(|_| AppError::ColumnNotFound(name.clone()))?;
+        parts.push(column.as_materialized_series().rechunk());
+    }
+
+    let values: Vec<Option<String>> = (0..df.height())
+        .map(|row| {
+            parts
+                .iter()
+                .map(|part| part.get(row).ok().and_then(|av| format_index_value(&av)))
+                .collect::<Option<Vec<_>>>()
+                .map(|values| values.join(sep))
+        })
+        .collect();
+
+    let name = cols.join("_");
+    df.with_column(Column::new(name.as_str().into(), values))?;
+    Ok(name)
+}
+
+/// Formats a value as part of a composite index label.
+///
+/// Whole floats are printed without a fractional part, and datetimes at midnight are
+/// printed as plain dates. Returns `None` for nulls.
+fn format_index_value(av: &AnyValue) -> Option<String> {
+    match av {
+        AnyValue::Null => None,
+        AnyValue::String(s) => Some(s.to_string()),
+        AnyValue::StringOwned(s) => Some(s.to_string()),
+        AnyValue::Float32(v) => Some(v.to_string()),
+        AnyValue::Float64(v) => Some(v.to_string()),
+        AnyValue::Date(_) | AnyValue::Datetime(_, _, _) => {
+            let ms = any_value_to_f64(av)? as i64;
+            let datetime = chrono::DateTime::from_timestamp_millis(ms)?.naive_utc();
+            if datetime.time() == chrono::NaiveTime::MIN {
+                Some(datetime.date().to_string())
+            } else {
+                Some(datetime.to_string())
+            }
+        }
+        other => Some(other.to_string()),
+    }
+}
+
+/// Converts every `Datetime` column from one time zone to another.
+///
+/// Columns without a time zone are first interpreted as local times in `from`; columns
+/// that already carry a time zone keep it as their source. The converted values are then
+/// stored as the wall-clock time of `to` (without time zone metadata), so that the
+/// millisecond values embedded in the plot, and shown on its UTC time axis, match the
+/// target time zone. Ambiguous local times resolve to the earliest instant, and
+/// non-existent ones (skipped by a DST change) become null.
+///
+/// # Errors
+///
+/// Returns an error if either time zone is not a valid IANA time zone or fixed offset.
+pub fn convert_time_zones(df: &mut DataFrame, from: &str, to: &str) -> Result<(), AppError> {
+    let from_tz = TimeZone::opt_try_new(Some(from))?.unwrap_or(TimeZone::UTC);
+    let to_tz = TimeZone::opt_try_new(Some(to))?.unwrap_or(TimeZone::UTC);
+    let ambiguous = StringChunked::from_slice("ambiguous".into(), &["earliest"]);
+
+    let datetime_columns: Vec<String> = df
+        .get_columns()
+        .iter()
+        .filter(|c| matches!(c.dtype(), DataType::Datetime(_, _)))
+        .map(|c| c.name().to_string())
+        .collect();
+    for name in datetime_columns {
+        let mut datetimes = df.column(&name)?.datetime()?.clone();
+        if datetimes.time_zone().is_none() {
+            datetimes =
+                replace_time_zone(&datetimes, Some(&from_tz), &ambiguous, NonExistent::Null)?;
+        }
+        datetimes.set_time_zone(to_tz.clone())?;
+        let local = replace_time_zone(&datetimes, None, &ambiguous, NonExistent::Null)?;
+        df.with_column(local.into_series())?;
+    }
+    Ok(())
+}
+
+/// Pivots a long-format DataFrame to wide format, with one column per unique `column` value.
+///
+/// For example, a DataFrame with the columns `[time, sensor_id, value]` pivoted with
+/// `index = "time"`, `column = "sensor_id"`, and `value = "value"` becomes
+/// `[time, <sensor 1>, <sensor 2>, ...]`. Rows keep the order in which each index value
+/// first appears. If an index value has several rows for the same column value, the first
+/// one is used.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFound` if any of the given columns does not exist.
+pub fn pivot_long_to_wide(
+    df: &DataFrame,
+    index: &str,
+    column: &str,
+    value: &str,
+) -> Result<DataFrame, AppError> {
+    for name in [index, column, value] {
+        if df.column(name).is_err() {
+            return Err(AppError::ColumnNotFound(name.to_string()));
+        }
+    }
+    let wide = polars::lazy::frame::pivot::pivot_stable(
+        df,
+        [column],
+        Some([index]),
+        Some([value]),
+        false,
+        Some(col("").first()),
+        None,
+    )?;
+    Ok(wide)
+}
+
+/// Collects the first `max_rows` rows of the numeric Y columns for a radar chart.
+///
+/// Columns without any numeric values (e.g., marker columns) are skipped. Each row is
+/// labeled with its X value.
+fn build_radar_data(x_series: &Series, y_series_list: &[Series], max_rows: usize) -> RadarData {
+    let numeric: Vec<&Series> = y_series_list
+        .iter()
+        .filter(|y| y.iter().any(|av| any_value_to_f64(&av).is_some()))
+        .collect();
+
+    let rows = (0..x_series.len().min(max_rows))
+        .map(|i| {
+            let label = match x_series.get(i) {
+                Ok(AnyValue::String(s)) => s.to_string(),
+                Ok(av) => av.to_string(),
+                Err(_) => i.to_string(),
+            };
+            let values = numeric
+                .iter()
+                .map(|y| y.get(i).ok().and_then(|av| any_value_to_f64(&av)))
+                .collect();
+            (label, values)
+        })
+        .collect();
+
+    RadarData {
+        columns: numeric.iter().map(|y| y.name().to_string()).collect(),
+        rows,
+    }
+}
+
+/// Bins the (x, y) pairs of two series into an `x_bins` by `y_bins` grid spanning
+/// their value ranges.
+///
+/// Rows where either value is missing or not numeric are skipped. Datetimes are binned
+/// by their millisecond timestamps and labeled as dates.
+fn build_heatmap_2d(x: &Series, y: &Series, x_bins: usize, y_bins: usize) -> Heatmap2dData {
+    let points: Vec<(f64, f64)> = x
+        .iter()
+        .zip(y.iter())
+        .filter_map(|(xv, yv)| Some((any_value_to_f64(&xv)?, any_value_to_f64(&yv)?)))
+        .filter(|(xv, yv)| xv.is_finite() && yv.is_finite())
+        .collect();
+
+    let (x_min, x_width) = bin_layout(points.iter().map(|p| p.0), x_bins);
+    let (y_min, y_width) = bin_layout(points.iter().map(|p| p.1), y_bins);
+    let bin_of = |v: f64, min: f64, width: f64, bins: usize| {
+        (((v - min) / width).floor().max(0.0) as usize).min(bins - 1)
+    };
+
+    let mut counts = vec![0usize; x_bins * y_bins];
+    for (xv, yv) in &points {
+        let xi = bin_of(*xv, x_min, x_width, x_bins);
+        let yi = bin_of(*yv, y_min, y_width, y_bins);
+        counts[yi * x_bins + xi] += 1;
+    }
+    let cells = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| **n > 0)
+        .map(|(i, n)| [i % x_bins, i / x_bins, *n])
+        .collect();
+
+    let labels = |series: &Series, min: f64, width: f64, bins: usize| -> Vec<String> {
+        (0..bins)
+            .map(|i| {
+                let center = min + (i as f64 + 0.5) * width;
+                match series.dtype() {
+                    DataType::Datetime(_, _) | DataType::Date => {
+                        chrono::DateTime::from_timestamp_millis(center as i64)
+                            .map_or(center.to_string(), |dt| {
+                                dt.naive_utc().format("%Y-%m-%d %H:%M").to_string()
+                            })
+                    }
+                    _ => format!("{:.4}", center)
+                        .trim_end_matches('0')
+                        .trim_end_matches('.')
+                        .to_string(),
+                }
+            })
+            .collect()
+    };
+
+    Heatmap2dData {
+        x_name: x.name().to_string(),
+        y_name: y.name().to_string(),
+        x_labels: labels(x, x_min, x_width, x_bins),
+        y_labels: labels(y, y_min, y_width, y_bins),
+        cells,
+    }
+}
+
+/// Returns the lower edge and width of equally sized bins covering the given values.
+///
+/// A constant (or empty) set of values gets bins spanning one unit around the value.
+fn bin_layout(values: impl Iterator<Item = f64>, bins: usize) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    match (min, max) {
+        (min, max) if min < max => (min, (max - min) / bins as f64),
+        (min, _) if min.is_finite() => (min - 0.5, 1.0 / bins as f64),
+        _ => (0.0, 1.0 / bins as f64),
+    }
 }
 
-/// Selects the X and Y series from a DataFrame and packages them for plotting.
+/// Computes `[lower whisker, Q1, median, Q3, upper whisker]` for a series.
 ///
-/// This function encapsulates the core logic for interpreting user intent from the CLI
-/// arguments and applying it to the loaded data.
+/// Quartiles use linear interpolation. The whiskers are the most extreme values within
+/// 1.5 times the interquartile range of Q1 and Q3 (Tukey's rule). Returns `None` for
+/// series without numeric values.
+fn five_number_summary(series: &Series) -> Result<Option<[f64; 5]>, AppError> {
+    let values = series.cast(&DataType::Float64)?;
+    let values = values.f64()?;
+    let quantile = |q: f64| values.quantile(q, QuantileMethod::Linear);
+    let (Some(q1), Some(median), Some(q3)) = (quantile(0.25)?, quantile(0.5)?, quantile(0.75)?)
+    else {
+        return Ok(None);
+    };
+
+    let iqr = q3 - q1;
+    let (low_fence, high_fence) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+    let within: Vec<f64> = values
+        .into_iter()
+        .flatten()
+        .filter(|v| *v >= low_fence && *v <= high_fence)
+        .collect();
+    let lower = within.iter().copied().fold(q1, f64::min);
+    let upper = within.iter().copied().fold(q3, f64::max);
+    Ok(Some([lower, q1, median, q3, upper]))
+}
+
+/// Reduces a series to a single value using `last` (last non-null value), `sum`, or `mean`.
 ///
-/// # Arguments
+/// Values that cannot be converted to a number are ignored. Returns `None` if the series
+/// has no numeric values.
+fn aggregate_series(series: &Series, agg: &str) -> Option<f64> {
+    let values: Vec<f64> = series
+        .iter()
+        .filter_map(|av| any_value_to_f64(&av))
+        .filter(|v| v.is_finite())
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    match agg {
+        "sum" => Some(values.iter().sum()),
+        "mean" => Some(values.iter().sum::<f64>() / values.len() as f64),
+        _ => values.last().copied(),
+    }
+}
+
+/// Returns the first non-null, non-blank value of a column as text (`--title-from-column`).
 ///
-/// * `df` - The input `DataFrame` loaded from a file.
-/// * `cli` - A reference to the parsed command-line arguments (`Cli` struct).
-/// * `file_path` - The path of the input file, used to generate a default title.
+/// Returns `None` if the column does not exist or has no such value.
+fn first_text_value(df: &DataFrame, name: &str) -> Option<String> {
+    let text = df.column(name).ok()?.cast(&DataType::String).ok()?;
+    let first = text
+        .str()
+        .ok()?
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string);
+    first
+}
+
+/// Combines an axis label and unit into an axis title such as `Temperature (°C)`.
 ///
-/// # Returns
+/// A unit without a label is used as the title on its own.
+fn axis_label(label: Option<&str>, unit: Option<&str>) -> Option<String> {
+    match (label, unit) {
+        (Some(label), Some(unit)) => Some(format!("{} ({})", label, unit)),
+        (Some(label), None) => Some(label.to_string()),
+        (None, Some(unit)) => Some(unit.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Loads highlighted X-axis regions from an annotation file.
 ///
-/// A `Result` containing a `PlotData` struct ready for the plotting engine,
-/// or an `AppError` if an appropriate X or Y series cannot be determined.
-pub fn prepare_plot_data(df: DataFrame, cli: &Cli, file_path: &Path) -> Result<PlotData, AppError> {
-    // 1. Determine the X-axis (index) series based on priority.
-    let (x_series, x_name) = select_x_series(&df, cli)?;
+/// The file is read through the regular `data_loader` pipeline, so `start` and `end`
+/// values may be numbers or datetimes. Rows whose `start` or `end` cannot be converted
+/// to a number are skipped. The `label` and `color` columns are optional.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFound` if the `start` or `end` column is missing.
+fn load_mark_regions(path: &Path, cli: &Cli) -> Result<Vec<MarkRegion>, AppError> {
+    let df = data_loader::load_dataframe(path, cli)?;
+
+    let get = |name: &str| -> Option<Series> {
+        df.column(name).ok().map(|c| c.as_series().unwrap().clone())
+    };
+    let starts = get("start").ok_or_else(|| AppError::ColumnNotFound("start".to_string()))?;
+    let ends = get("end").ok_or_else(|| AppError::ColumnNotFound("end".to_string()))?;
+    let labels = get("label");
+    let colors = get("color");
+
+    let text_at = |series: &Option<Series>, i: usize| -> Option<String> {
+        match series.as_ref()?.get(i).ok()? {
+            AnyValue::Null => None,
+            AnyValue::String(s) => Some(s.to_string()),
+            av => Some(av.to_string()),
+        }
+    };
+
+    let mut regions = Vec::new();
+    for i in 0..df.height() {
+        let start = starts.get(i).ok().and_then(|av| any_value_to_f64(&av));
+        let end = ends.get(i).ok().and_then(|av| any_value_to_f64(&av));
+        if let (Some(start), Some(end)) = (start, end) {
+            regions.push(MarkRegion {
+                start,
+                end,
+                label: text_at(&labels, i).unwrap_or_default(),
+                color: text_at(&colors, i).unwrap_or_else(|| DEFAULT_REGION_COLOR.to_string()),
+            });
+        }
+    }
 
     if cli.debug {
-        println!(
-            "  -> Selected X-axis column: '{}' with {} values",
-            x_name,
-            x_series.len()
+        status!(
+            "  -> Loaded {} highlighted regions from '{}'",
+            regions.len(),
+            path.display()
         );
     }
 
-    // 2. Determine the Y-axis series.
-    let y_series_list = select_y_series(&df, cli, &x_name)?;
+    Ok(regions)
+}
 
-    let mut final_series_list = Vec::new();
-    let mut downsampled = false;
+/// Splits each (name, X, Y) series into one series per category of `shape_column`.
+///
+/// Categories are taken in order of first appearance, and each is assigned a marker shape
+/// from `CATEGORY_SHAPES` (cycling if there are more categories than shapes). The new
+/// series are named `<series> [<category>]`. Rows with a null category are dropped, and
+/// the category column itself is never plotted.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFound` if `shape_column` does not exist.
+fn split_by_shape_column(
+    df: &DataFrame,
+    series_pairs: Vec<SeriesEntry>,
+    shape_column: &str,
+) -> Result<(Vec<SeriesEntry>, HashMap<String, SeriesShape>), AppError> {
+    let categories = df
+        .column(shape_column)
+        .map_err(|_| AppError::ColumnNotFound(shape_column.to_string()))?
+        .as_series()
+        .unwrap()
+        .cast(&DataType::String)?;
+    let category_values: Vec<String> = categories
+        .unique_stable()?
+        .str()?
+        .into_iter()
+        .flatten()
+        .map(|v| v.to_string())
+        .collect();
 
-    // 3. Process each series, applying downsampling if necessary.
-    for y_series in y_series_list {
-        let y_name = y_series.name().to_string();
-        if y_series.len() > cli.downsample_threshold {
-            println!(
-                "  -> Downsampling '{}' from {} to {} points...",
-                y_name,
-                y_series.len(),
-                cli.downsample_threshold
+    let mut split_pairs = Vec::new();
+    let mut shapes = HashMap::new();
+    let pairs = series_pairs
+        .into_iter()
+        .filter(|(name, _, _)| name != shape_column);
+    for (group, (y_name, x_series, y_series)) in pairs.enumerate() {
+        for (ci, category) in category_values.iter().enumerate() {
+            let mask = categories.str()?.equal(category.as_str());
+            let name = format!("{} [{}]", y_name, category);
+            shapes.insert(
+                name.clone(),
+                SeriesShape {
+                    symbol: CATEGORY_SHAPES[ci % CATEGORY_SHAPES.len()].to_string(),
+                    color_group: group,
+                },
             );
-            let (ds_x, ds_y) = downsample_series(&x_series, &y_series, cli.downsample_threshold);
-            final_series_list.push((y_name, ds_x, ds_y));
-            downsampled = true;
-            continue;
+            split_pairs.push((name, x_series.filter(&mask)?, y_series.filter(&mask)?));
+        }
+    }
+    Ok((split_pairs, shapes))
+}
+
+/// Splits each series into one series per value of a grouping column.
+///
+/// Split series are named `<series>/<group>` and keep the rows of their group, so all
+/// groups share the same X-axis. Only the first `max_groups` groups (in order of first
+/// appearance) are plotted, with a warning if there are more. Rows with a null group are
+/// dropped, and the grouping column itself is never plotted.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFound` if `group_column` does not exist.
+fn split_by_group_column(
+    df: &DataFrame,
+    series_pairs: Vec<SeriesEntry>,
+    group_column: &str,
+    max_groups: usize,
+) -> Result<Vec<SeriesEntry>, AppError> {
+    let groups = df
+        .column(group_column)
+        .map_err(|_| AppError::ColumnNotFound(group_column.to_string()))?
+        .as_materialized_series()
+        .cast(&DataType::String)?;
+    let mut group_values: Vec<String> = groups
+        .unique_stable()?
+        .str()?
+        .into_iter()
+        .flatten()
+        .map(|v| v.to_string())
+        .collect();
+    if group_values.len() > max_groups {
+        status!(
+            "  -> Warning: Column '{}' has {} groups; only the first {} are plotted (see --max-groups).",
+            group_column,
+            group_values.len(),
+            max_groups
+        );
+        group_values.truncate(max_groups);
+    }
+
+    let mut split_pairs = Vec::new();
+    let pairs = series_pairs
+        .into_iter()
+        .filter(|(name, _, _)| name != group_column);
+    for (y_name, x_series, y_series) in pairs {
+        for group in &group_values {
+            let mask = groups.str()?.equal(group.as_str());
+            split_pairs.push((
+                format!("{}/{}", y_name, group),
+                x_series.filter(&mask)?,
+                y_series.filter(&mask)?,
+            ));
         }
+    }
+    Ok(split_pairs)
+}
+
+/// Returns, for each row, the index of the previous row of the same group, or null for
+/// the first row (of its group). Without groups, this is simply the previous row.
+fn previous_rows(groups: Option<&Series>, len: usize) -> Result<IdxCa, AppError> {
+    let Some(groups) = groups else {
+        return Ok((0..len)
+            .map(|i| i.checked_sub(1).map(|p| p as IdxSize))
+            .collect());
+    };
+    let mut last_row: HashMap<Option<&str>, IdxSize> = HashMap::new();
+    Ok(groups
+        .str()?
+        .into_iter()
+        .enumerate()
+        .map(|(i, group)| last_row.insert(group, i as IdxSize))
+        .collect())
+}
+
+/// Returns the difference of each value of a numeric series and the value at its
+/// `previous` row; rows without a previous row are null.
+///
+/// Unsigned integers are converted to `Int64` first, so that decreases stay negative.
+fn first_difference(y_series: &Series, previous: &IdxCa) -> Result<Series, AppError> {
+    let values = if y_series.dtype().is_unsigned_integer() {
+        y_series.cast(&DataType::Int64)?
+    } else {
+        y_series.clone()
+    };
+    let difference = (&values - &values.take(previous)?)?;
+    Ok(difference.with_name(y_series.name().clone()))
+}
+
+/// Returns the running total of a numeric series within each group, skipping nulls.
+fn cum_sum_by_group(y_series: &Series, groups: &Series) -> Result<Series, AppError> {
+    let frame = DataFrame::new(vec![
+        y_series.clone().with_name("value".into()).into_column(),
+        groups.clone().with_name("group".into()).into_column(),
+    ])?;
+    let totals = frame
+        .lazy()
+        .select([col("value").cum_sum(false).over([col("group")])])
+        .collect()?;
+    Ok(totals
+        .column("value")?
+        .as_materialized_series()
+        .clone()
+        .with_name(y_series.name().clone()))
+}
+
+/// Renames a transformed Y series to `<name>_<suffix>`, recording the source column it
+/// came from in `sources`.
+fn rename_with_suffix(series: &mut Series, suffix: &str, sources: &mut HashMap<String, String>) {
+    let name = series.name().to_string();
+    let renamed = format!("{}_{}", name, suffix);
+    let source = sources.remove(&name).unwrap_or(name);
+    sources.insert(renamed.clone(), source);
+    series.rename(renamed.into());
+}
 
-        // If not downsampling, use the original series.
-        final_series_list.push((y_name, x_series.clone(), y_series));
+/// Aggregates a series into regular buckets of `every_ms` milliseconds of the X-axis.
+///
+/// Each bucket covers `[start, start + every_ms)` and is represented by its start; its
+/// Y value is the mean of the numeric Y values that fall into it. The buckets form a
+/// regular grid from the first to the last X value, so buckets without data have a
+/// null Y value. The X series becomes a millisecond `Datetime` series for date and
+/// datetime axes. Non-numeric Y series (e.g., marker columns) are returned unchanged.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidResample` if the grid would have more than
+/// `MAX_RESAMPLE_BUCKETS` buckets.
+fn resample_series(
+    x_series: &Series,
+    y_series: &Series,
+    every_ms: i64,
+) -> Result<(Series, Series), AppError> {
+    if !y_series.dtype().is_numeric() {
+        return Ok((x_series.clone(), y_series.clone()));
     }
+    let points: Vec<(i64, f64)> = x_series
+        .iter()
+        .zip(y_series.iter())
+        .filter_map(|(x, y)| {
+            let bucket = (any_value_to_f64(&x)? as i64).div_euclid(every_ms);
+            Some((bucket, any_value_to_f64(&y)?))
+        })
+        .collect();
+    let (Some(first), Some(last)) = (
+        points.iter().map(|(b, _)| *b).min(),
+        points.iter().map(|(b, _)| *b).max(),
+    ) else {
+        return Ok((x_series.clear(), y_series.clear()));
+    };
 
-    // 4. Determine the plot title.
-    let title = cli.title.clone().unwrap_or_else(|| {
-        file_path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
+    let bucket_count = (last - first + 1) as usize;
+    if bucket_count > MAX_RESAMPLE_BUCKETS {
+        return Err(AppError::InvalidResample(
+            bucket_count,
+            MAX_RESAMPLE_BUCKETS,
+        ));
+    }
+    let mut sums = vec![0.0; bucket_count];
+    let mut counts = vec![0usize; bucket_count];
+    for (bucket, y) in points {
+        let i = (bucket - first) as usize;
+        sums[i] += y;
+        counts[i] += 1;
+    }
+
+    let starts: Vec<i64> = (first..=last).map(|b| b * every_ms).collect();
+    let means: Vec<Option<f64>> = sums
+        .iter()
+        .zip(&counts)
+        .map(|(sum, count)| (*count > 0).then(|| sum / *count as f64))
+        .collect();
+    let x_resampled = match x_series.dtype() {
+        DataType::Datetime(_, _) | DataType::Date => {
+            Series::new(x_series.name().clone(), starts)
+                .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+        }
+        _ => Series::new(x_series.name().clone(), starts),
+    };
+    Ok((x_resampled, Series::new(y_series.name().clone(), means)))
+}
+
+/// Sorts series by `name` (ascending), or by their maximum (`max`) or variance
+/// (`variance`) in descending order.
+///
+/// Series without numeric values (e.g., marker columns) are placed last, keeping
+/// their relative order.
+fn sort_series(series_pairs: &mut [SeriesEntry], mode: &str) {
+    if mode == "name" {
+        series_pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        return;
+    }
+    let key = |y: &Series| -> Option<f64> {
+        if !y.dtype().is_numeric() {
+            return None;
+        }
+        let values = y.cast(&DataType::Float64).ok()?;
+        let values = values.f64().ok()?;
+        if mode == "max" {
+            values.max()
+        } else {
+            values.var(1)
+        }
+    };
+    let keys: HashMap<String, f64> = series_pairs
+        .iter()
+        .filter_map(|(name, _, y)| Some((name.clone(), key(y).filter(|v| !v.is_nan())?)))
+        .collect();
+    series_pairs.sort_by(|a, b| match (keys.get(&a.0), keys.get(&b.0)) {
+        (Some(ka), Some(kb)) => kb.total_cmp(ka),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
     });
+}
 
-    Ok(PlotData {
-        title,
-        series_list: final_series_list,
-        special_marker: cli.vertical_marker.clone(),
-        autoscale_y: !cli.no_autoscale_y,
-        animations: cli.animations,
-        max_decimals: cli.max_decimals,
-        use_white_theme: cli.white_theme,
-        large_mode_threshold: cli.large_mode_threshold,
-        downsampled,
-    })
+/// Computes the trailing moving average of a series over `window` points (`--rolling-mean`).
+///
+/// The first points average over as many values as are available, so the trend starts
+/// with the data; null values are skipped.
+fn rolling_mean(y: &Series, window: usize) -> Result<Series, AppError> {
+    let values = y.cast(&DataType::Float64)?;
+    Ok(values.rolling_mean(RollingOptionsFixedWindow {
+        window_size: window.max(1),
+        min_periods: 1,
+        ..Default::default()
+    })?)
+}
+
+/// Computes the empirical cumulative distribution function (ECDF) of a series.
+///
+/// Returns the sorted numeric values (the new X series) and, for the i-th value,
+/// the cumulative probability `(i + 1) / N` (the new Y series). Null and non-numeric
+/// values are ignored.
+fn compute_ecdf(s: &Series) -> (Series, Series) {
+    let mut values: Vec<f64> = s
+        .iter()
+        .filter_map(|av| any_value_to_f64(&av))
+        .filter(|v| v.is_finite())
+        .collect();
+    values.sort_by(f64::total_cmp);
+
+    let n = values.len() as f64;
+    let probabilities: Vec<f64> = (1..=values.len()).map(|i| i as f64 / n).collect();
+    (
+        Series::new(s.name().clone(), values),
+        Series::new("probability".into(), probabilities),
+    )
+}
+
+/// Builds a lag plot from a series: `y[t]` as the X series against `y[t + lag]` as the Y series.
+///
+/// The X series is named `<col>_t`. Both series are empty if the series has no more
+/// than `lag` values.
+fn compute_lag_plot(s: &Series, lag: usize) -> (Series, Series) {
+    let n = s.len().saturating_sub(lag);
+    let mut x_lagged = s.slice(0, n);
+    x_lagged.rename(format!("{}_t", s.name()).into());
+    let y_lagged = s.slice(lag as i64, n);
+    (x_lagged, y_lagged)
+}
+
+/// Min-max scales a numeric series to the [0, 1] range.
+///
+/// Constant series (where the minimum equals the maximum) and non-numeric series
+/// are returned unchanged.
+fn normalize_series(s: &Series) -> Series {
+    if !s.dtype().is_primitive_numeric() {
+        return s.clone();
+    }
+    let (Ok(Some(min)), Ok(Some(max))) = (s.min::<f64>(), s.max::<f64>()) else {
+        return s.clone();
+    };
+    if max == min {
+        status!(
+            "  -> Warning: Column '{}' is constant; it is not normalized.",
+            s.name()
+        );
+        return s.clone();
+    }
+    let Ok(values) = s.cast(&DataType::Float64) else {
+        return s.clone();
+    };
+    (values - min) / (max - min)
+}
+
+/// Standardizes a numeric series by subtracting its mean and dividing by its
+/// sample standard deviation.
+///
+/// Constant series (zero standard deviation), series with fewer than two values,
+/// and non-numeric series are returned unchanged.
+fn zscore_series(s: &Series) -> Result<Series, AppError> {
+    if !s.dtype().is_primitive_numeric() {
+        return Ok(s.clone());
+    }
+    let (Some(mean), Some(std)) = (s.mean(), s.std(1)) else {
+        return Ok(s.clone());
+    };
+    if std == 0.0 {
+        status!(
+            "  -> Warning: Column '{}' is constant; it is not standardized.",
+            s.name()
+        );
+        return Ok(s.clone());
+    }
+    let values = s.cast(&DataType::Float64)?;
+    Ok((values - mean) / std)
+}
+
+/// Replaces NaN values in a `Float32` or `Float64` series with nulls.
+///
+/// Series of any other type are returned unchanged.
+fn replace_nans_with_null(s: &Series) -> Result<Series, AppError> {
+    let series = match s.dtype() {
+        DataType::Float32 => {
+            let ca = s.f32()?;
+            ca.set(&ca.is_nan(), None)?.into_series()
+        }
+        DataType::Float64 => {
+            let ca = s.f64()?;
+            ca.set(&ca.is_nan(), None)?.into_series()
+        }
+        _ => s.clone(),
+    };
+    Ok(series)
+}
+
+/// Replaces positive and negative infinity in a `Float32` or `Float64` series with nulls.
+///
+/// Series of any other type are returned unchanged.
+fn replace_infs_with_null(s: &Series) -> Result<Series, AppError> {
+    let series = match s.dtype() {
+        DataType::Float32 => {
+            let ca = s.f32()?;
+            ca.set(&ca.is_infinite(), None)?.into_series()
+        }
+        DataType::Float64 => {
+            let ca = s.f64()?;
+            ca.set(&ca.is_infinite(), None)?.into_series()
+        }
+        _ => s.clone(),
+    };
+    Ok(series)
+}
+
+/// Removes the points whose X value is below the `p`-th or above the `(100 - p)`-th
+/// percentile of the X series.
+///
+/// Datetimes are compared by their timestamps. Series with a categorical (string)
+/// X-axis are returned unchanged.
+fn clip_x_percentile(x: &Series, y: &Series, p: f64) -> Result<(Series, Series), AppError> {
+    if matches!(x.dtype(), DataType::String) || p <= 0.0 {
+        return Ok((x.clone(), y.clone()));
+    }
+    let values = x.to_physical_repr().cast(&DataType::Float64)?;
+    let values = values.f64()?;
+    let (Some(low), Some(high)) = (
+        values.quantile(p / 100.0, QuantileMethod::Linear)?,
+        values.quantile(1.0 - p / 100.0, QuantileMethod::Linear)?,
+    ) else {
+        return Ok((x.clone(), y.clone()));
+    };
+    let mask = values.gt_eq(low) & values.lt_eq(high);
+    Ok((x.filter(&mask)?, y.filter(&mask)?))
 }
 
 /// Downsamples a pair of X/Y series using the LTTB algorithm.
 ///
-/// Note: This converts the data to `f64` for processing, so original types like
-/// Datetime are lost and become numeric representations (e.g., milliseconds).
-fn downsample_series(x_series: &Series, y_series: &Series, threshold: usize) -> (Series, Series) {
+/// The points are converted to `f64` for LTTB; the X series is cast back to its original
+/// `Datetime` or `Date` type afterwards, so a time axis stays a time axis. Other X types
+/// are returned as `Float64`.
+fn downsample_series(
+    x_series: &Series,
+    y_series: &Series,
+    threshold: usize,
+) -> Result<(Series, Series), AppError> {
     let points: Vec<lttb::DataPoint> = x_series
         .iter()
         .zip(y_series.iter())
@@ -126,33 +2212,68 @@ fn downsample_series(x_series: &Series, y_series: &Series, threshold: usize) ->
         })
         .collect();
 
+    let x_dtype = match x_series.dtype() {
+        dtype @ (DataType::Datetime(_, _) | DataType::Date) => dtype.clone(),
+        _ => DataType::Float64,
+    };
+
     if points.is_empty() {
-        return (
-            Series::new_empty(x_series.name().clone(), &DataType::Float64),
+        return Ok((
+            Series::new_empty(x_series.name().clone(), &x_dtype),
             Series::new_empty(y_series.name().clone(), &DataType::Float64),
-        );
+        ));
     }
 
     let downsampled_points = lttb::lttb(points, threshold);
+    let (xs, ys): (Vec<f64>, Vec<f64>) = downsampled_points.iter().map(|p| (p.x, p.y)).unzip();
 
-    let mut x_builder = PrimitiveChunkedBuilder::<Float64Type>::new(
-        "x_downsampled".into(),
-        downsampled_points.len(),
-    );
-    let mut y_builder = PrimitiveChunkedBuilder::<Float64Type>::new(
-        "y_downsampled".into(),
-        downsampled_points.len(),
-    );
+    Ok((
+        typed_x_series("x_downsampled".into(), xs, &x_dtype)?,
+        Series::new("y_downsampled".into(), ys),
+    ))
+}
 
-    for p in downsampled_points {
-        x_builder.append_value(p.x);
-        y_builder.append_value(p.y);
-    }
+/// Builds an X series of the given type from numeric values, such as the values embedded
+/// in a generated page or the output of LTTB downsampling.
+///
+/// Dates and datetimes are represented as milliseconds since the epoch, so they are
+/// converted back to the target type's unit.
+pub fn typed_x_series(
+    name: PlSmallStr,
+    values: Vec<f64>,
+    dtype: &DataType,
+) -> Result<Series, AppError> {
+    let series = match dtype {
+        DataType::Datetime(unit, _) => {
+            let per_ms = match unit {
+                TimeUnit::Nanoseconds => 1_000_000,
+                TimeUnit::Microseconds => 1_000,
+                TimeUnit::Milliseconds => 1,
+            };
+            let raw: Vec<i64> = values.iter().map(|ms| *ms as i64 * per_ms).collect();
+            Series::new(name, raw).cast(dtype)?
+        }
+        DataType::Date => {
+            let days: Vec<i32> = values.iter().map(|ms| (ms / 86_400_000.0) as i32).collect();
+            Series::new(name, days).cast(dtype)?
+        }
+        _ => Series::new(name, values).cast(dtype)?,
+    };
+    Ok(series)
+}
 
-    (
-        x_builder.finish().into_series(),
-        y_builder.finish().into_series(),
-    )
+/// The largest integer magnitude that an `f64` can represent exactly (2^53 - 1).
+const MAX_SAFE_INTEGER: u128 = (1 << 53) - 1;
+
+/// Checks whether a 128-bit integer series holds values that cannot be represented
+/// exactly as `f64`. Returns false for all other data types.
+fn exceeds_safe_integer_range(series: &Series) -> bool {
+    if !matches!(series.dtype(), DataType::Int128) {
+        return false;
+    }
+    series
+        .iter()
+        .any(|av| matches!(av, AnyValue::Int128(v) if v.unsigned_abs() > MAX_SAFE_INTEGER))
 }
 
 /// Safely check a string series for any values containing the special marker.
@@ -181,7 +2302,7 @@ fn check_string_series_for_marker(series: &Series, cli: &Cli) -> bool {
 /// The selection priority is as follows:
 /// 1.  The column specified by the `--index` flag.
 /// 2.  The first column of the DataFrame if `--use-first-column` is specified.
-/// 3.  A column named `sample_index` (common for audio data).
+/// 3.  A column named `sample_index`, `time_seconds`, or `frequency_hz` (common for audio data).
 /// 4.  The first `Datetime` or `Date` column found.
 /// 5.  A fallback generated series of row numbers named `row_index`.
 ///
@@ -189,8 +2310,8 @@ fn check_string_series_for_marker(series: &Series, cli: &Cli) -> bool {
 ///
 /// A tuple containing the selected `Series` and its name.
 fn select_x_series(df: &DataFrame, cli: &Cli) -> Result<(Series, String), AppError> {
-    // Priority 1: --index flag
-    if let Some(index_name) = &cli.index {
+    // Priority 1: --index flag, or the --pivot-index column of a pivoted DataFrame
+    if let Some(index_name) = cli.index.as_ref().or(cli.pivot_index.as_ref()) {
         let series = df
             .column(index_name)
             .map_err(|_| AppError::ColumnNotFound(index_name.clone()))?
@@ -215,10 +2336,12 @@ fn select_x_series(df: &DataFrame, cli: &Cli) -> Result<(Series, String), AppErr
         return Ok((series, name));
     }
 
-    // Priority 3: Audio-friendly default — use 'sample_index' if present.
-    if df.get_column_names().iter().any(|&n| n == "sample_index") {
-        let series = df.column("sample_index")?.as_series().unwrap().clone();
-        return Ok((series, "sample_index".to_string()));
+    // Priority 3: Audio-friendly default — use 'sample_index' (or 'time_seconds' with
+    // --audio-time-axis, or 'frequency_hz' for an --fft spectrum) if present.
+    for name in ["sample_index", "time_seconds", "frequency_hz"] {
+        if let Ok(column) = df.column(name) {
+            return Ok((column.as_series().unwrap().clone(), name.to_string()));
+        }
     }
 
     // Priority 4: Auto-detect first datetime column.
@@ -233,7 +2356,7 @@ fn select_x_series(df: &DataFrame, cli: &Cli) -> Result<(Series, String), AppErr
     }
 
     // Priority 5: Fallback to row numbers.
-    println!("  -> Warning: No index specified and no datetime column found. Using row numbers as index.");
+    status!("  -> Warning: No index specified and no datetime column found. Using row numbers as index.");
     let row_count = df.height() as u32;
     let series = Series::new("row_index".into(), (0..row_count).collect::<Vec<u32>>());
     Ok((series, "row_index".to_string()))
@@ -242,25 +2365,33 @@ fn select_x_series(df: &DataFrame, cli: &Cli) -> Result<(Series, String), AppErr
 /// Selects the Y-axis series to be plotted.
 ///
 /// Two main cases are handled:
-/// 1.  If the `--columns` flag is provided, only the specified columns are used.
-/// 2.  Otherwise, all numeric columns (excluding the selected X-axis column) are used.
+/// 1.  If `columns` (the resolved `--columns` flag) is provided, only those columns are used.
+/// 2.  Otherwise, all numeric columns (excluding the selected X-axis column and any
+///     `--exclude-columns`) are used, limited to those matching `--columns-regex` if given.
 ///     String columns containing the special marker are also included.
 ///
+/// Boolean columns are included in both cases and plotted as 0/1, keeping their names.
+///
 /// # Errors
 ///
 /// Returns `AppError::NoNumericColumns` if no suitable Y-axis columns can be found.
-fn select_y_series(df: &DataFrame, cli: &Cli, x_name: &str) -> Result<Vec<Series>, AppError> {
+fn select_y_series(
+    df: &DataFrame,
+    cli: &Cli,
+    columns: Option<&[String]>,
+    x_name: &str,
+) -> Result<Vec<Series>, AppError> {
     let mut y_series_list: Vec<Series> = Vec::new();
 
     if cli.debug {
-        println!("  -> Scanning columns for Y-axis data...");
+        status!("  -> Scanning columns for Y-axis data...");
     }
 
     // Case 1: --columns flag is used.
-    if let Some(columns) = &cli.columns {
+    if let Some(columns) = columns {
         for col_name in columns {
             if cli.debug {
-                println!("  -> Processing specified column '{}'", col_name);
+                status!("  -> Processing specified column '{}'", col_name);
             }
 
             let series = df
@@ -269,33 +2400,43 @@ fn select_y_series(df: &DataFrame, cli: &Cli, x_name: &str) -> Result<Vec<Series
                 .as_series()
                 .unwrap()
                 .clone();
-            y_series_list.push(series);
+            y_series_list.push(bool_series_to_numeric(series)?);
         }
     }
-    // Case 2: Default - use all numeric columns and special string columns.
+    // Case 2: Default - use all numeric columns and special string columns,
+    // optionally only those whose names match --columns-regex.
     else {
         for column in df.get_columns() {
-            if column.name() != x_name {
+            let name_matches = cli
+                .columns_regex
+                .as_ref()
+                .is_none_or(|re| re.is_match(column.name()));
+            let excluded = cli
+                .exclude_columns
+                .iter()
+                .chain(&cli.title_from_column)
+                .any(|c| c.as_str() == column.name().as_str());
+            if column.name() != x_name && name_matches && !excluded {
                 let is_numeric = column.dtype().is_numeric();
                 let series = column.as_series().unwrap();
 
-                let should_include = if let DataType::String = column.dtype() {
-                    check_string_series_for_marker(series, cli)
-                } else {
-                    is_numeric
+                let should_include = match column.dtype() {
+                    DataType::String => check_string_series_for_marker(series, cli),
+                    DataType::Boolean => true,
+                    _ => is_numeric,
                 };
 
                 if should_include {
                     if cli.debug {
-                        println!(
+                        status!(
                             "  -> Including Y-axis column '{}' ({} values)",
                             series.name(),
                             series.len()
                         );
                     }
-                    y_series_list.push(series.clone());
+                    y_series_list.push(bool_series_to_numeric(series.clone())?);
                 } else if cli.debug {
-                    println!("  -> Skipping column '{}'", series.name());
+                    status!("  -> Skipping column '{}'", series.name());
                 }
             }
         }
@@ -308,6 +2449,136 @@ fn select_y_series(df: &DataFrame, cli: &Cli, x_name: &str) -> Result<Vec<Series
     }
 }
 
+/// Casts a Boolean series to `Int8` (`true` is 1, `false` is 0), so it can be plotted.
+///
+/// The cast keeps the series name, so the legend shows the original column name.
+/// Other series are returned unchanged.
+fn bool_series_to_numeric(series: Series) -> Result<Series, AppError> {
+    if matches!(series.dtype(), DataType::Boolean) {
+        Ok(series.cast(&DataType::Int8)?)
+    } else {
+        Ok(series)
+    }
+}
+
+/// Returns the latest X value over all series with a `Datetime` or `Date` X-axis,
+/// in milliseconds since the epoch.
+fn latest_datetime_ms(series_list: &[(String, Series, Series)]) -> Option<f64> {
+    series_list
+        .iter()
+        .filter(|(_, x, _)| matches!(x.dtype(), DataType::Datetime(_, _) | DataType::Date))
+        .flat_map(|(_, x, _)| x.iter().filter_map(|av| any_value_to_f64(&av)))
+        .reduce(f64::max)
+}
+
+/// Prints the minimum and maximum of the X series, formatted as datetimes for
+/// `Datetime` and `Date` columns.
+fn print_x_range(x_series: &Series) {
+    let (min, max) = x_series
+        .iter()
+        .filter_map(|av| any_value_to_f64(&av))
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if min > max {
+        status!("  -> X range: no numeric or datetime values");
+        return;
+    }
+
+    let format = |v: f64| match x_series.dtype() {
+        DataType::Datetime(_, _) | DataType::Date => {
+            chrono::DateTime::from_timestamp_millis(v as i64).map_or(v.to_string(), |dt| {
+                let format = if matches!(x_series.dtype(), DataType::Date) {
+                    "%Y-%m-%d"
+                } else {
+                    "%Y-%m-%dT%H:%M:%S"
+                };
+                dt.naive_utc().format(format).to_string()
+            })
+        }
+        _ => v.to_string(),
+    };
+    status!("  -> X range: {} to {}", format(min), format(max));
+}
+
+/// Resolves `--columns` entries to column names.
+///
+/// Entries naming an existing column are kept as they are; other entries that are
+/// valid zero-based column indices (e.g., `0` or `5`) are replaced by the name of that
+/// column. Anything else is kept unchanged, to be reported by `validate_columns`.
+fn resolve_column_specs(df: &DataFrame, specs: &[String]) -> Vec<String> {
+    let names = df.get_column_names();
+    specs
+        .iter()
+        .map(|spec| {
+            if names.iter().any(|n| n.as_str() == spec) {
+                return spec.clone();
+            }
+            spec.parse::<usize>()
+                .ok()
+                .and_then(|i| names.get(i))
+                .map_or_else(|| spec.clone(), |name| name.to_string())
+        })
+        .collect()
+}
+
+/// Checks that all the given column names exist in the DataFrame.
+///
+/// # Errors
+///
+/// Returns `AppError::ColumnNotFoundSimilar` (or `AppError::ColumnNotFound` if no
+/// existing column has a similar name) for a single missing column, and
+/// `AppError::ColumnsNotFound` listing every missing column if there are several.
+fn validate_columns(df: &DataFrame, columns: &[String]) -> Result<(), AppError> {
+    let available: Vec<&str> = df.get_column_names().iter().map(|n| n.as_str()).collect();
+    let mut errors: Vec<AppError> = columns
+        .iter()
+        .filter(|name| !available.contains(&name.as_str()))
+        .map(|name| match closest_column_name(name, &available) {
+            Some(similar) => AppError::ColumnNotFoundSimilar(name.clone(), similar.to_string()),
+            None => AppError::ColumnNotFound(name.clone()),
+        })
+        .collect();
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        _ => Err(AppError::ColumnsNotFound(
+            errors.iter().map(|e| e.to_string()).collect(),
+        )),
+    }
+}
+
+/// Finds the available column name closest to `name`, ignoring case.
+///
+/// Only names within an edit distance of a third of the name's length (at least 2)
+/// are considered similar.
+fn closest_column_name<'a>(name: &str, available: &[&'a str]) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+    available
+        .iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 /// Converts a Polars `AnyValue` to an `Option<f64>`.
 ///
 /// This helper is used for calculating min/max ranges for axes and for downsampling.
@@ -325,6 +2596,7 @@ pub fn any_value_to_f64(av: &AnyValue) -> Option<f64> {
         AnyValue::Int16(v) => Some(*v as f64),
         AnyValue::Int32(v) => Some(*v as f64),
         AnyValue::Int64(v) => Some(*v as f64),
+        AnyValue::Int128(v) => Some(*v as f64),
         AnyValue::Float32(v) => Some(*v as f64),
         AnyValue::Float64(v) => Some(*v),
         AnyValue::Date(days) => Some((*days as i64 as f64) * 86_400_000.0),
@@ -336,3 +2608,109 @@ pub fn any_value_to_f64(av: &AnyValue) -> Option<f64> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn parse_cli(args: &[&str]) -> Cli {
+        Cli::parse_from(["scatters", "d.csv"].iter().chain(args))
+    }
+
+    fn diff_test_df() -> DataFrame {
+        df!(
+            "x" => [1.0, 2.0, 3.0, 4.0, 5.0],
+            "a" => [1.0, 3.0, 6.0, 10.0, 15.0],
+            "b" => [5.0, 4.0, 3.0, 2.0, 1.0],
+            "g" => ["p", "q", "p", "q", "p"]
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn diff_with_group_by_keeps_rows_aligned() {
+        let cli = parse_cli(&[
+            "--index",
+            "x",
+            "--columns",
+            "a",
+            "--diff",
+            "--group-by",
+            "g",
+        ]);
+        let plot = prepare_plot_data(diff_test_df(), &cli, Path::new("d.csv")).unwrap();
+
+        let mut points: Vec<(f64, f64)> = plot
+            .series_list
+            .iter()
+            .flat_map(|(_, x, y)| {
+                let x = x.cast(&DataType::Float64).unwrap();
+                let y = y.cast(&DataType::Float64).unwrap();
+                let pairs: Vec<_> = x
+                    .f64()
+                    .unwrap()
+                    .into_no_null_iter()
+                    .zip(y.f64().unwrap().into_no_null_iter())
+                    .collect();
+                pairs
+            })
+            .collect();
+        points.sort_by(|p, q| p.0.total_cmp(&q.0));
+        // p: rows 1, 3, 5 (a = 1, 6, 15); q: rows 2, 4 (a = 3, 10)
+        assert_eq!(points, vec![(3.0, 5.0), (4.0, 7.0), (5.0, 9.0)]);
+        let names: Vec<&str> = plot
+            .series_list
+            .iter()
+            .map(|(n, _, _)| n.as_str())
+            .collect();
+        assert_eq!(names, vec!["a_diff/p", "a_diff/q"]);
+    }
+
+    #[test]
+    fn diff_renames_series_and_keeps_right_axis() {
+        let cli = parse_cli(&["--index", "x", "--diff", "--right-axis", "b"]);
+        let plot = prepare_plot_data(diff_test_df(), &cli, Path::new("d.csv")).unwrap();
+
+        assert_eq!(plot.right_axis_series, vec!["b_diff".to_string()]);
+        let (_, _, b) = plot
+            .series_list
+            .iter()
+            .find(|(name, _, _)| name == "b_diff")
+            .unwrap();
+        let values: Vec<f64> = b.f64().unwrap().into_no_null_iter().collect();
+        assert_eq!(values, vec![-1.0, -1.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn cumsum_with_group_by_totals_each_group() {
+        let cli = parse_cli(&[
+            "--index",
+            "x",
+            "--columns",
+            "a",
+            "--cumsum",
+            "--group-by",
+            "g",
+        ]);
+        let plot = prepare_plot_data(diff_test_df(), &cli, Path::new("d.csv")).unwrap();
+
+        let totals: Vec<(&str, Vec<f64>)> = plot
+            .series_list
+            .iter()
+            .map(|(name, _, y)| {
+                (
+                    name.as_str(),
+                    y.f64().unwrap().into_no_null_iter().collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            totals,
+            vec![
+                ("a_cumsum/p", vec![1.0, 7.0, 22.0]),
+                ("a_cumsum/q", vec![3.0, 13.0])
+            ]
+        );
+    }
+}
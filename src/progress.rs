@@ -0,0 +1,44 @@
+//! Progress bar shown while processing several files.
+//!
+//! Status messages are printed with the `status!` macro, which suspends the bar while
+//! printing so that messages appear above it instead of being drawn over by it. The bar
+//! is only drawn when stderr is a terminal.
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::RwLock;
+
+/// Layout of the bar: percentage, bar, file count, remaining time, and current file.
+const BAR_TEMPLATE: &str = "{percent:>3}% [{bar:30}] {pos}/{len} ETA {eta} {msg}";
+
+/// The progress bar of the current run, if one is shown.
+static BAR: RwLock<Option<ProgressBar>> = RwLock::new(None);
+
+/// Shows a progress bar for `len` files and returns it.
+///
+/// Messages printed with `status!` are routed around the bar until `finish` is called.
+pub fn start(len: usize) -> ProgressBar {
+    let style = ProgressStyle::default_bar()
+        .template(BAR_TEMPLATE)
+        .expect("the progress bar template is valid")
+        .progress_chars("=> ");
+    let bar = ProgressBar::new(len as u64).with_style(style);
+    *BAR.write().unwrap() = Some(bar.clone());
+    bar
+}
+
+/// Removes the progress bar, if one is shown.
+pub fn finish() {
+    if let Some(bar) = BAR.write().unwrap().take() {
+        bar.finish_and_clear();
+    }
+}
+
+/// Runs `f` with the progress bar hidden, redrawing it afterwards.
+///
+/// Without a progress bar, `f` is simply run.
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    match BAR.read().unwrap().as_ref() {
+        Some(bar) => bar.suspend(f),
+        None => f(),
+    }
+}
@@ -0,0 +1,135 @@
+//! Machine-readable plot summaries written next to the HTML output (`--json-summary`).
+//!
+//! Each generated plot `<stem>.html` gets a `<stem>.summary.json` sidecar describing its input,
+//! its series, and their X and Y ranges, so that pipelines producing many plots can
+//! inspect them without parsing the HTML.
+
+use crate::error::AppError;
+use crate::processing::{any_value_to_f64, PlotData};
+use polars::prelude::AnyValue;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Metadata of one generated plot, serialized as the JSON sidecar.
+///
+/// Datetime X values are reported as milliseconds since the Unix epoch, like in the plot.
+#[derive(Serialize)]
+pub struct JsonSummary {
+    /// File name of the input data file.
+    pub input: String,
+    /// File name of the generated HTML file.
+    pub output: String,
+    /// The title of the plot.
+    pub title: String,
+    /// Number of plotted series.
+    pub series_count: usize,
+    /// Smallest numeric X value over all series.
+    pub x_min: Option<f64>,
+    /// Largest numeric X value over all series.
+    pub x_max: Option<f64>,
+    /// Smallest numeric Y value over all series.
+    pub y_min: Option<f64>,
+    /// Largest numeric Y value over all series.
+    pub y_max: Option<f64>,
+    /// True if any series was downsampled.
+    pub downsampled: bool,
+    /// Per-series metadata, in plotting order.
+    pub series: Vec<SeriesSummary>,
+}
+
+/// Metadata of one plotted series.
+#[derive(Serialize)]
+pub struct SeriesSummary {
+    pub name: String,
+    /// Number of plotted points, excluding missing values and vertical markers.
+    pub rows: usize,
+    pub x_min: Option<f64>,
+    pub x_max: Option<f64>,
+    pub y_min: Option<f64>,
+    pub y_max: Option<f64>,
+}
+
+impl JsonSummary {
+    /// Summarizes the series of a prepared plot.
+    ///
+    /// Points are counted and measured the same way as they are plotted: rows with a
+    /// missing X or Y value and special marker rows are skipped, and only finite
+    /// values contribute to the ranges.
+    pub fn new(plot_data: &PlotData, input_path: &Path, output_path: &Path) -> Self {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let series: Vec<SeriesSummary> = plot_data
+            .series_list
+            .iter()
+            .map(|(name, x_series, y_series)| {
+                let mut summary = SeriesSummary {
+                    name: name.clone(),
+                    rows: 0,
+                    x_min: None,
+                    x_max: None,
+                    y_min: None,
+                    y_max: None,
+                };
+                for (x_val, y_val) in x_series.iter().zip(y_series.iter()) {
+                    let is_marker =
+                        matches!(&y_val, AnyValue::String(s) if *s == plot_data.special_marker);
+                    if matches!(x_val, AnyValue::Null)
+                        || matches!(y_val, AnyValue::Null)
+                        || is_marker
+                    {
+                        continue;
+                    }
+                    summary.rows += 1;
+                    if let Some(x) = any_value_to_f64(&x_val).filter(|v| v.is_finite()) {
+                        summary.x_min = Some(summary.x_min.map_or(x, |m| m.min(x)));
+                        summary.x_max = Some(summary.x_max.map_or(x, |m| m.max(x)));
+                    }
+                    if let Some(y) = any_value_to_f64(&y_val).filter(|v| v.is_finite()) {
+                        summary.y_min = Some(summary.y_min.map_or(y, |m| m.min(y)));
+                        summary.y_max = Some(summary.y_max.map_or(y, |m| m.max(y)));
+                    }
+                }
+                summary
+            })
+            .collect();
+
+        JsonSummary {
+            input: file_name(input_path),
+            output: file_name(output_path),
+            title: plot_data.title.clone(),
+            series_count: series.len(),
+            x_min: series.iter().filter_map(|s| s.x_min).reduce(f64::min),
+            x_max: series.iter().filter_map(|s| s.x_max).reduce(f64::max),
+            y_min: series.iter().filter_map(|s| s.y_min).reduce(f64::min),
+            y_max: series.iter().filter_map(|s| s.y_max).reduce(f64::max),
+            downsampled: plot_data.downsampled,
+            series,
+        }
+    }
+}
+
+/// Writes the JSON sidecar of a generated plot next to its HTML file, e.g.
+/// `data.summary.json` for `data.html`.
+///
+/// The `.summary` infix keeps the sidecar from overwriting a `data.json` input file
+/// when plots are saved next to their inputs.
+///
+/// # Errors
+///
+/// Returns an error if the summary cannot be serialized or the file cannot be written.
+pub fn write_json_summary(
+    plot_data: &PlotData,
+    input_path: &Path,
+    output_path: &Path,
+) -> Result<PathBuf, AppError> {
+    let summary = JsonSummary::new(plot_data, input_path, output_path);
+    let path = output_path.with_extension("summary.json");
+    fs::write(&path, serde_json::to_string_pretty(&summary)?)?;
+    Ok(path)
+}
@@ -0,0 +1,115 @@
+//! Support for incrementally updating a previously generated plot (`--append`).
+//!
+//! Generated pages wrap their embedded series JSON in marker comments, so the
+//! data of an existing plot can be recovered and merged with newly loaded data
+//! instead of regenerating the plot from scratch.
+
+use crate::error::AppError;
+use crate::processing::{any_value_to_f64, typed_x_series, PlotData};
+use polars::prelude::*;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// The comment placed right before the embedded series JSON in a generated page.
+pub const SERIES_START_MARKER: &str = "/*SCATTERS_SERIES_START*/";
+
+/// The comment placed right after the embedded series JSON in a generated page.
+pub const SERIES_END_MARKER: &str = "/*SCATTERS_SERIES_END*/";
+
+/// A series recovered from a previously generated HTML plot.
+pub struct ExistingSeries {
+    /// The series name, as shown in the legend.
+    pub name: String,
+    /// The plotted points as numeric (x, y) pairs. Datetimes are in milliseconds.
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Extracts the embedded series data from a previously generated HTML plot.
+///
+/// Returns `None` if the file cannot be read, was not generated by this tool,
+/// or its embedded data cannot be parsed. Points with non-numeric coordinates
+/// (e.g., on a categorical X-axis) are skipped.
+pub fn parse_existing_html(path: &Path) -> Option<Vec<ExistingSeries>> {
+    let html = fs::read_to_string(path).ok()?;
+    let start = html.find(SERIES_START_MARKER)? + SERIES_START_MARKER.len();
+    let end = start + html[start..].find(SERIES_END_MARKER)?;
+    let series: Vec<Value> = serde_json::from_str(&format!("[{}]", &html[start..end])).ok()?;
+
+    let parsed = series
+        .iter()
+        .filter_map(|s| {
+            let name = s.get("name")?.as_str()?.to_string();
+            let points = s
+                .get("data")?
+                .as_array()?
+                .iter()
+                .filter_map(|p| Some((p.get(0)?.as_f64()?, p.get(1)?.as_f64()?)))
+                .collect();
+            Some(ExistingSeries { name, points })
+        })
+        .collect();
+    Some(parsed)
+}
+
+/// Merges the series of an existing plot into freshly prepared plot data.
+///
+/// For each numeric series that also exists in the old plot, only the new points whose
+/// X value is greater than the last X value of the old plot are kept, and they are
+/// appended after the old points. Series that are new, non-numeric (e.g., marker
+/// columns), or plotted on a categorical X-axis are left unchanged; series that only
+/// exist in the old plot are dropped.
+///
+/// # Errors
+///
+/// Returns an error if the merged series cannot be built.
+pub fn merge_existing_series(
+    plot_data: &mut PlotData,
+    existing: Vec<ExistingSeries>,
+) -> Result<(), AppError> {
+    for (name, x_series, y_series) in plot_data.series_list.iter_mut() {
+        let Some(old) = existing.iter().find(|s| plain_series_name(&s.name) == name) else {
+            continue;
+        };
+        if !y_series.dtype().is_numeric() || old.points.is_empty() {
+            continue;
+        }
+        if !matches!(x_series.dtype(), DataType::Datetime(_, _) | DataType::Date)
+            && !x_series.dtype().is_numeric()
+        {
+            continue;
+        }
+
+        let last_x = old
+            .points
+            .iter()
+            .map(|(x, _)| *x)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let mask: BooleanChunked = x_series
+            .iter()
+            .map(|av| any_value_to_f64(&av).is_some_and(|x| x > last_x))
+            .collect();
+        let new_x = x_series.filter(&mask)?;
+        let new_y = y_series.filter(&mask)?.cast(&DataType::Float64)?;
+
+        let (old_x, old_y): (Vec<f64>, Vec<f64>) = old.points.iter().copied().unzip();
+        let mut merged_x = typed_x_series(x_series.name().clone(), old_x, x_series.dtype())?;
+        let mut merged_y = Series::new(y_series.name().clone(), old_y);
+        merged_x.append(&new_x)?;
+        merged_y.append(&new_y)?;
+
+        *x_series = merged_x.rechunk();
+        *y_series = merged_y.rechunk();
+    }
+    Ok(())
+}
+
+/// Strips the point count that `--show-n-points` appends to legend labels, e.g.
+/// `temp (4892 pts)` becomes `temp`.
+fn plain_series_name(label: &str) -> &str {
+    label
+        .strip_suffix(" pts)")
+        .and_then(|rest| rest.rsplit_once(" ("))
+        .filter(|(_, count)| !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()))
+        .map_or(label, |(name, _)| name)
+}